@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use image::RgbImage;
+
+use crate::engine::input::EventType;
+use crate::engine::render::*;
+use crate::engine::window::*;
+
+// Everything the game loop needs from the outside world : input, time and a place to put finished frames.
+pub trait Backend {
+    fn get_events(&mut self) -> Vec<(Key, EventType)>;
+    fn should_close(&self) -> bool;
+    fn get_time(&self) -> f64;
+    fn set_time(&mut self, time : f64);
+    fn present(&mut self, image : RgbImage) -> Result<(), String>;
+}
+
+pub struct WindowBackend {
+    window : Window,
+    render : Render
+}
+
+impl WindowBackend {
+    pub fn open(parameters : WindowParameters) -> WindowBackend {
+        let (width, height) = (parameters.width, parameters.height);
+        let window = Window::open(parameters);
+        let render = Render::new(width, height);
+
+        WindowBackend { window, render }
+    }
+}
+
+impl Backend for WindowBackend {
+    fn get_events(&mut self) -> Vec<(Key, EventType)> { self.window.get_events() }
+    fn should_close(&self) -> bool { self.window.should_close() }
+    fn get_time(&self) -> f64 { self.window.get_time() }
+    fn set_time(&mut self, time : f64) { self.window.set_time(time); }
+    fn present(&mut self, image : RgbImage) -> Result<(), String> {
+        self.render.render(&mut self.window, image);
+        Ok(())
+    }
+}
+
+// Renders into memory with a fixed timestep, no GLFW window or OpenGL context is created.
+pub struct HeadlessBackend {
+    frame_count : u32,
+    rendered_frames : u32,
+    delta_time : f64,
+    output_dir : Option<PathBuf>
+}
+
+impl HeadlessBackend {
+    pub fn new(frame_count : u32, delta_time : f64, output_dir : Option<PathBuf>) -> Result<HeadlessBackend, String> {
+        if let Some(dir) = &output_dir {
+            std::fs::create_dir_all(dir).map_err(|e| format!("can't create output dir {} : {}", dir.display(), e))?;
+        }
+
+        Ok(HeadlessBackend { frame_count, rendered_frames : 0, delta_time, output_dir })
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn get_events(&mut self) -> Vec<(Key, EventType)> { Vec::new() }
+    fn should_close(&self) -> bool { self.rendered_frames >= self.frame_count }
+    fn get_time(&self) -> f64 { self.delta_time }
+    fn set_time(&mut self, _time : f64) { }
+
+    fn present(&mut self, image : RgbImage) -> Result<(), String> {
+        if let Some(dir) = &self.output_dir {
+            // Frames are stored bottom-up, flip them to get regular images.
+            let path = dir.join(format!("frame_{:05}.png", self.rendered_frames));
+            image::imageops::flip_vertical(&image).save(&path).map_err(|e| format!("can't save frame {} : {}", path.display(), e))?;
+        }
+
+        self.rendered_frames += 1;
+        Ok(())
+    }
+}
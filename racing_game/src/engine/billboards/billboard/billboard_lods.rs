@@ -1,21 +1,16 @@
-use std::slice;
-use std::mem;
-
 use image::*;
+use image::imageops::FilterType;
 
 use crate::engine::common::{IVec2, ImageOps};
 
-struct SpriteDescr {
-    pos_x : u32,
-    pos_y : u32,
-
-    width : u32,
-    height : u32
-}
+// Generated lods are halved until they are this narrow.
+const MIN_LOD_WIDTH : u32 = 8;
 
 struct Lod{
     image : RgbaImage,
-    scale : f32
+    scale : f32,
+    // Every pixel of the image is drawn as a square of this size.
+    pixel_size : u32
 }
 
 pub struct BillboardLods{
@@ -23,29 +18,30 @@ pub struct BillboardLods{
 }
 
 impl BillboardLods{
-    pub fn new(spritesheet : &RgbaImage, meta_file_content : &[u8]) -> BillboardLods {
-        let sprites_data_raw = meta_file_content.as_ptr() as *const SpriteDescr;
-        let sprites_data_count = meta_file_content.len() / mem::size_of::<SpriteDescr>();
-        let sprites_data = unsafe { slice::from_raw_parts(sprites_data_raw, sprites_data_count) };
-
-        let mut lods : Vec<Lod> = Vec::with_capacity(sprites_data.len());
-        for sprite_data in sprites_data {
-            let mut lod = Lod { image : RgbaImage::new(sprite_data.width, sprite_data.height), scale : 1.0 };
-            
-            if lods.len() != 0 {
-                lod.scale = (lod.image.width() as f32) / (lods[0].image.width() as f32);
-            }
-
-            for x in 0..sprite_data.width {
-                for y in 0..sprite_data.height {
-                    lod.image.put_pixel(x, y, *spritesheet.get_pixel(x + sprite_data.pos_x, y + sprite_data.pos_y));
-                }
-            }
+    // Image pixels are squares of pixel_size at scale 1, so pixel art isn't stored upscaled.
+    // Lods down to the image size reuse it with smaller pixels, smaller ones are downscaled from it.
+    pub fn from_image(image : &RgbaImage, pixel_size : u32) -> BillboardLods {
+        let mut lods = Vec::new();
+        let mut lod_pixel_size = pixel_size;
+        while lod_pixel_size >= 1 && (lods.is_empty() || image.width() * lod_pixel_size >= MIN_LOD_WIDTH) {
+            lods.push(Lod { image : image.clone(), scale : lod_pixel_size as f32 / pixel_size as f32, pixel_size : lod_pixel_size });
+            lod_pixel_size /= 2;
+        }
 
-            lods.push(lod);
+        let full_width = image.width() * pixel_size;
+        let (mut width, mut height) = (image.width() / 2, image.height() / 2);
+        while width >= MIN_LOD_WIDTH && height > 0 {
+            lods.push(Lod { image : imageops::resize(image, width, height, FilterType::Nearest), scale : width as f32 / full_width as f32, pixel_size : 1 });
+            width /= 2;
+            height /= 2;
         }
 
-        BillboardLods { lods } 
+        BillboardLods { lods }
+    }
+
+    // Width of the most detailed lod, it is rendered as is at scale 1.
+    pub fn get_width(&self) -> u32 {
+        self.lods[0].image.width() * self.lods[0].pixel_size
     }
 
     fn get_lod_id(&self, scale : f32) -> u32 {
@@ -67,12 +63,12 @@ impl BillboardLods{
         return closest_lod;
     }
 
-    pub fn render(&self, pos_x : i32, pos_y : i32, scale : f32, buffer : &mut RgbImage) {
+    pub fn render(&self, pos_x : i32, pos_y : i32, min_y : i32, scale : f32, buffer : &mut RgbImage) {
         let lod = &self.lods[self.get_lod_id(scale) as usize];
 
-        let left_bottom_x = pos_x - lod.image.width() as i32 / 2;
+        let left_bottom_x = pos_x - (lod.image.width() * lod.pixel_size) as i32 / 2;
         let position = IVec2::new(left_bottom_x as isize, pos_y as isize);
         
-        ImageOps::overlay_rgba(buffer, &lod.image, &position);
+        ImageOps::overlay_rgba_above(buffer, &lod.image, &position, min_y as isize, lod.pixel_size);
     }
 }
\ No newline at end of file
@@ -17,8 +17,13 @@ pub struct BillboardFactory {
 }
 
 impl BillboardFactory {
-    pub fn new(spritesheet : &RgbaImage, meta_file_content : &[u8]) -> BillboardFactory {
-        BillboardFactory { lods : Rc::from(BillboardLods::new(spritesheet, meta_file_content)) }
+    pub fn from_image(image : &RgbaImage) -> BillboardFactory {
+        Self::from_pixel_art(image, 1)
+    }
+
+    // Every pixel of the image is as big as pixel_size pixels of the full size billboard.
+    pub fn from_pixel_art(image : &RgbaImage, pixel_size : u32) -> BillboardFactory {
+        BillboardFactory { lods : Rc::from(BillboardLods::from_image(image, pixel_size)) }
     }
 
     pub fn construct(&self, road_distance : f32, offset : f32) -> Billboard {
@@ -26,8 +31,25 @@ impl BillboardFactory {
     }
 }  
 
+// Billboards are the same when they stand at the same place and share the sprite.
+impl PartialEq for Billboard {
+    fn eq(&self, other : &Billboard) -> bool {
+        self.road_distance == other.road_distance && self.offset == other.offset && Rc::ptr_eq(&self.lods, &other.lods)
+    }
+}
+
 impl Billboard{
-    pub fn render(&self, pos_x : i32, pos_y : i32, scale : f32, buffer : &mut RgbImage) {
-        self.lods.render(pos_x, pos_y, scale, buffer);
+    // Position on the track driven backwards.
+    pub fn reversed(&self, length : f32) -> Billboard {
+        Billboard { lods : self.lods.clone(), road_distance : length - self.road_distance, offset : -self.offset }
+    }
+
+    pub fn get_width(&self, screen_width : u32) -> f32 {
+        self.lods.get_width() as f32 / screen_width as f32
+    }
+
+    // Part of the billboard below min_y is hidden by the ground in front of it.
+    pub fn render(&self, pos_x : i32, pos_y : i32, min_y : i32, scale : f32, buffer : &mut RgbImage) {
+        self.lods.render(pos_x, pos_y, min_y, scale, buffer);
     }
 }
\ No newline at end of file
@@ -2,37 +2,36 @@ use crate::image::{ RgbImage };
 
 use super::track::YData;
 use super::camera::Camera;
+use super::common::Math;
 
 mod billboard;
 pub use billboard::*;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct BillboardId(pub u32);
 
 #[derive(Clone)]
 pub struct Billboards {
     static_billboards : Vec<Billboard>,
-    dynamic_billboards : Vec<(BillboardId, Billboard)>
+    dynamic_billboards : Vec<(BillboardId, Billboard)>,
+    // Ids aren't reused, so removing a billboard doesn't change the others.
+    next_dynamic_id : u32
 }
 
 impl Billboards {
     pub fn new() -> Billboards {
-        Billboards { static_billboards : Vec::new(), dynamic_billboards : Vec::new() }
+        Billboards { static_billboards : Vec::new(), dynamic_billboards : Vec::new(), next_dynamic_id : 0 }
     }
 
-    pub fn add_static(&mut self, billboard : Billboard) {
-        for i in 0..self.static_billboards.len() {
-            if self.static_billboards[i].road_distance > billboard.road_distance {
-                self.static_billboards.insert(i, billboard);
-                return;
-            }
-        }
-
-        self.static_billboards.push(billboard);
-    }   
+    // Static billboards are sorted once, so the whole scenery of a road is added at once.
+    pub fn add_static(&mut self, billboards : Vec<Billboard>) {
+        self.static_billboards.extend(billboards);
+        self.static_billboards.sort_by(|a, b| a.road_distance.partial_cmp(&b.road_distance).unwrap());
+    }
 
     pub fn add_dynamic(&mut self, billboard : Billboard) -> BillboardId {
-        let id = BillboardId(self.dynamic_billboards.len() as u32);
+        let id = BillboardId(self.next_dynamic_id);
+        self.next_dynamic_id += 1;
 
         for i in 0..self.dynamic_billboards.len() {
             if self.dynamic_billboards[i].1.road_distance > billboard.road_distance {
@@ -45,6 +44,15 @@ impl Billboards {
         id
     }
 
+    // Billboards stay sorted by distance, dynamic ones keep their ids.
+    pub fn reversed(&self, length : f32) -> Billboards {
+        Billboards {
+            static_billboards : self.static_billboards.iter().rev().map(|billboard| billboard.reversed(length)).collect(),
+            dynamic_billboards : self.dynamic_billboards.iter().rev().map(|(id, billboard)| (*id, billboard.reversed(length))).collect(),
+            next_dynamic_id : self.next_dynamic_id
+        }
+    }
+
     pub fn get_dynamic_mut(&mut self, id : BillboardId) -> &mut Billboard {
         for dyn_billboard in &mut self.dynamic_billboards {
             if (dyn_billboard.0).0 == id.0 { return &mut dyn_billboard.1; }
@@ -53,34 +61,59 @@ impl Billboards {
         panic!();
     }
 
-    fn get_sorted_billboards(&self) -> Vec<&Billboard> {
-        let mut billboards : Vec<&Billboard> = Vec::with_capacity(self.static_billboards.len() + self.dynamic_billboards.len());
+    pub fn remove_dynamic(&mut self, id : BillboardId) {
+        self.dynamic_billboards.retain(|(dyn_id, _)| dyn_id.0 != id.0);
+    }
+
+    // Static billboards from the start to the end distance.
+    fn get_static_range(&self, start : f32, end : f32) -> &[Billboard] {
+        let first = self.static_billboards.partition_point(|billboard| billboard.road_distance < start);
+        let last = self.static_billboards.partition_point(|billboard| billboard.road_distance <= end);
+        &self.static_billboards[first..Math::max(first, last)]
+    }
 
-        for i in &self.static_billboards { billboards.push(i); }
-        for i in &self.dynamic_billboards { billboards.push(&i.1); }
+    // First billboard intersecting the box centered at the road distance and offset.
+    pub fn find_collision(&self, road_distance : f32, offset : f32, width : f32, depth : f32, screen_width : u32) -> Option<&Billboard> {
+        self.get_static_range(road_distance - depth * 0.5, road_distance + depth * 0.5).iter()
+        .chain(self.dynamic_billboards.iter().map(|(_, billboard)| billboard))
+        .find(|billboard| {
+            (billboard.road_distance - road_distance).abs() < depth * 0.5 &&
+            (billboard.offset - offset).abs() < (billboard.get_width(screen_width) + width) * 0.5
+        })
+    }
 
-        billboards.sort_by(|a, b| a.road_distance.partial_cmp(&b.road_distance).unwrap());
+    // Static billboards are already sorted, only the few dynamic ones are sorted and merged into them.
+    fn get_visible_billboards(&self, camera : &Camera) -> Vec<&Billboard> {
+        let (start, end) = (camera.road_distance, camera.road_distance + camera.far_plane);
+        let static_billboards = self.get_static_range(start, end);
+        let mut dynamic_billboards : Vec<&Billboard> = self.dynamic_billboards.iter()
+        .map(|(_, billboard)| billboard)
+        .filter(|billboard| billboard.road_distance >= start && billboard.road_distance <= end)
+        .collect();
+        dynamic_billboards.sort_by(|a, b| a.road_distance.partial_cmp(&b.road_distance).unwrap());
+
+        let mut billboards = Vec::with_capacity(static_billboards.len() + dynamic_billboards.len());
+        let mut dynamic_billboards = dynamic_billboards.into_iter().peekable();
+        for billboard in static_billboards {
+            while let Some(dynamic_billboard) = dynamic_billboards.next_if(|dynamic_billboard| dynamic_billboard.road_distance < billboard.road_distance) {
+                billboards.push(dynamic_billboard);
+            }
+            billboards.push(billboard);
+        }
+        billboards.extend(dynamic_billboards);
 
-        return billboards;
+        billboards
     }
 
     pub fn render_all(&self, camera : &Camera, y_data : &Vec<YData>, buffer : &mut RgbImage) {
-        let billboards = self.get_sorted_billboards();
+        let billboards = self.get_visible_billboards(camera);
+        if billboards.is_empty() { return; }
 
         let mut global_distance;
         let mut prev_global_distance = camera.far_plane;
 
-        let mut curr_render_billboard : i32 = billboards.len() as i32 - 1;
-        let far_plane_global = camera.road_distance + camera.far_plane;
-
-        // Find farthest visible billboard.
-        for billboard in billboards.iter().rev() {
-            if billboard.road_distance <= far_plane_global { break; }
-            curr_render_billboard -= 1;
-            if curr_render_billboard == -1 { return; }
-        }
-
-        let mut curr_render_billboard = curr_render_billboard as usize;
+        // Start from the farthest visible billboard.
+        let mut curr_render_billboard = billboards.len() - 1;
 
         // Render from back to front.
         for y in (0..y_data.len()).rev() {
@@ -94,10 +127,20 @@ impl Billboards {
                     break;
                 }
 
-                let billboard_scale = camera.screen_dist / y_data[y].distance;
+                // Billboard can stand behind a crest, then it is projected below this row and partially hidden.
+                let distance = billboard_distance - camera.road_distance;
+                let height = if y + 1 < y_data.len() {
+                    let t = (distance - y_data[y].distance) / (y_data[y + 1].distance - y_data[y].distance);
+                    Math::lerp(y_data[y].height, y_data[y + 1].height, t)
+                } else { 
+                    y_data[y].height 
+                };
+                let billboard_y = Math::min(camera.get_screen_y(distance, height, buffer.height()).round() as i32, y as i32);
+
+                let billboard_scale = camera.screen_dist / distance;
                 let billboard_offset = 0.5/*half of screen*/ + billboards[curr_render_billboard].offset * billboard_scale + y_data[y].norm_road_offset;
 
-                billboards[curr_render_billboard].render((billboard_offset * (buffer.width() as f32)) as i32, y as i32, billboard_scale, buffer);
+                billboards[curr_render_billboard].render((billboard_offset * (buffer.width() as f32)) as i32, billboard_y, y as i32, billboard_scale, buffer);
                 
                 if curr_render_billboard == 0 {
                     curr_render_billboard = billboards.len();
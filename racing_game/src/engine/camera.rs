@@ -6,4 +6,13 @@ pub struct Camera{
     pub pitch : f32,
     pub road_distance : f32,
     pub x_offset : f32
-}
\ No newline at end of file
+}
+
+impl Camera {
+    // Fractional screen row of the ground point. Height is relative to the road under the camera.
+    pub fn get_screen_y(&self, distance : f32, height : f32, frame_height : u32) -> f32 {
+        let drop_per_distance = (self.y_pos - height) / distance;
+        let y_norm = (1.0 - drop_per_distance * self.screen_dist / self.viewport_height) / self.pitch;
+        y_norm * frame_height as f32
+    }
+}
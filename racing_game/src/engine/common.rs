@@ -21,9 +21,16 @@ impl FloodFillRange {
 
 impl ImageOps {
     pub fn overlay_rgba(bottom : &mut RgbImage, top : &RgbaImage, position : &IVec2) {
-        for x in Math::max(0, -position.x)..Math::min(top.width() as isize, bottom.width() as isize - position.x) {
-            for y in Math::max(0, -position.y)..Math::min(top.height() as isize, bottom.height() as isize - position.y){
-                let image_pixel = top.get_pixel(x as u32, top.height() - y as u32 - 1);
+        Self::overlay_rgba_above(bottom, top, position, 0, 1);
+    }
+
+    // Rows of the bottom image below min_y are left untouched, every pixel of the top image covers a square of pixel_size pixels.
+    pub fn overlay_rgba_above(bottom : &mut RgbImage, top : &RgbaImage, position : &IVec2, min_y : isize, pixel_size : u32) {
+        let pixel_size = pixel_size as isize;
+        let (width, height) = (top.width() as isize * pixel_size, top.height() as isize * pixel_size);
+        for x in Math::max(0, -position.x)..Math::min(width, bottom.width() as isize - position.x) {
+            for y in Math::max(0, min_y - position.y)..Math::min(height, bottom.height() as isize - position.y){
+                let image_pixel = top.get_pixel((x / pixel_size) as u32, top.height() - (y / pixel_size) as u32 - 1);
                 if image_pixel[3] == 0 { continue; }
                 bottom.put_pixel((position.x + x) as u32, (position.y + y) as u32, Rgb([image_pixel[0], image_pixel[1], image_pixel[2]]));
             }
@@ -38,6 +45,17 @@ impl ImageOps {
         }
     }
 
+    // White color leaves the image as is.
+    pub fn multiply(image : &mut RgbImage, color : &Rgb<u8>) {
+        if *color == Rgb([255, 255, 255]) { return; }
+
+        for pixel in image.pixels_mut() {
+            for channel in 0..3 {
+                pixel[channel] = (pixel[channel] as u32 * color[channel] as u32 / 255) as u8;
+            }
+        }
+    }
+
     // Draw only lines that are fully inside the buffer.
     pub fn draw_line_one_pixel(buffer : &mut RgbImage, start : &IVec2, end : &IVec2, color : &Rgb::<u8>) {
         if start.x < 0 || start.x >= buffer.width() as isize { return; }
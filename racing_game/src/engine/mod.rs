@@ -8,5 +8,7 @@ pub mod traffic;
 pub mod render;
 pub mod window;
 pub mod input;
+pub mod backend;
+pub mod replay;
 
 pub mod ui;
\ No newline at end of file
@@ -0,0 +1,185 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use image::RgbImage;
+
+use crate::engine::backend::Backend;
+use crate::engine::input::EventType;
+use crate::engine::window::*;
+
+const RECORD_HEADER : &str = "INPUT_RECORD 1";
+
+#[derive(Clone)]
+pub struct RecordedFrame {
+    pub delta_time : f64,
+    pub events : Vec<(Key, EventType)>
+}
+
+impl RecordedFrame {
+    // <delta time> [<key code> <P|R>]*
+    fn to_line(&self) -> String {
+        let mut line = self.delta_time.to_string();
+        for (key, event_type) in &self.events {
+            let event_type = match event_type {
+                EventType::Pressed => { "P" }
+                EventType::Released => { "R" }
+            };
+            line += &format!(" {} {}", *key as i32, event_type);
+        }
+
+        line
+    }
+
+    fn from_line(line : &str) -> Result<RecordedFrame, String> {
+        let mut tokens = line.split_whitespace();
+        let delta_time = tokens.next()
+        .ok_or(String::from("empty frame"))?
+        .parse::<f64>()
+        .map_err(|_| format!("incorrect delta time in frame [{}]", line))?;
+
+        let mut events = Vec::new();
+        while let Some(key_code) = tokens.next() {
+            let key = key_code.parse::<i32>().ok().and_then(key_from_code).ok_or(format!("incorrect key [{}]", key_code))?;
+            let event_type = match tokens.next() {
+                Some("P") => { EventType::Pressed }
+                Some("R") => { EventType::Released }
+                _ => { return Err(format!("incorrect event type in frame [{}]", line)); }
+            };
+            events.push((key, event_type));
+        }
+
+        Ok(RecordedFrame { delta_time, events })
+    }
+}
+
+pub struct InputRecord {
+    pub properties : Vec<(String, String)>,
+    pub frames : Vec<RecordedFrame>
+}
+
+impl InputRecord {
+    pub fn load(path : &Path) -> Result<InputRecord, String> {
+        let file = File::open(path).map_err(|e| format!("can't open input record {} : {}", path.display(), e))?;
+        let mut lines = BufReader::new(file).lines().map(|line| line.map_err(|e| e.to_string()));
+
+        if lines.next().transpose()?.as_deref() != Some(RECORD_HEADER) {
+            return Err(format!("{} is not an input record", path.display()));
+        }
+
+        let mut properties = Vec::new();
+        let mut frames = Vec::new();
+        for line in lines {
+            let line = line?;
+            // # <key> <value>
+            if let Some(property) = line.strip_prefix('#') {
+                let mut tokens = property.split_whitespace();
+                if let (Some(key), Some(value)) = (tokens.next(), tokens.next()) {
+                    properties.push((String::from(key), String::from(value)));
+                }
+                continue;
+            }
+
+            frames.push(RecordedFrame::from_line(&line)?);
+        }
+
+        Ok(InputRecord { properties, frames })
+    }
+}
+
+// Passes everything through to the wrapped backend and writes every frame to the file as it happens,
+// so the record survives a crash.
+pub struct RecordingBackend {
+    inner : Box<dyn Backend>,
+    path : PathBuf,
+    writer : BufWriter<File>,
+    frame_delta_time : f64,
+    // Events can't fail, so the first write error waits for the next present.
+    write_error : Option<String>
+}
+
+impl RecordingBackend {
+    // Properties are whatever else is needed to reproduce the run, like the world seed.
+    pub fn new(path : &Path, properties : &[(&str, String)], inner : Box<dyn Backend>) -> Result<RecordingBackend, String> {
+        let write_error = |e : std::io::Error| format!("can't write input record {} : {}", path.display(), e);
+        let file = File::create(path).map_err(|e| format!("can't create input record {} : {}", path.display(), e))?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", RECORD_HEADER).map_err(write_error)?;
+        for (key, value) in properties {
+            writeln!(writer, "# {} {}", key, value).map_err(write_error)?;
+        }
+
+        Ok(RecordingBackend { inner, path : path.to_path_buf(), writer, frame_delta_time : 0.0, write_error : None })
+    }
+}
+
+impl Backend for RecordingBackend {
+    fn get_events(&mut self) -> Vec<(Key, EventType)> {
+        let events = self.inner.get_events();
+
+        let frame = RecordedFrame { delta_time : self.frame_delta_time, events : events.clone() };
+        let written = writeln!(self.writer, "{}", frame.to_line()).and_then(|_| self.writer.flush());
+        if let (Err(e), None) = (written, &self.write_error) {
+            self.write_error = Some(format!("can't write input record {} : {}", self.path.display(), e));
+        }
+
+        events
+    }
+
+    fn should_close(&self) -> bool { self.inner.should_close() }
+
+    fn get_time(&self) -> f64 { self.inner.get_time() }
+
+    fn set_time(&mut self, time : f64) {
+        // Game loop reads the time and resets it right away, so it is the frame delta time.
+        self.frame_delta_time = self.inner.get_time();
+        self.inner.set_time(time);
+    }
+
+    fn present(&mut self, image : RgbImage) -> Result<(), String> {
+        if let Some(e) = self.write_error.take() { return Err(e); }
+        self.inner.present(image)
+    }
+}
+
+// Feeds recorded events and delta times instead of the wrapped backend's ones, frames are still presented by it.
+pub struct ReplayBackend {
+    inner : Box<dyn Backend>,
+    record : InputRecord,
+    curr_frame : usize
+}
+
+impl ReplayBackend {
+    pub fn new(record : InputRecord, inner : Box<dyn Backend>) -> ReplayBackend {
+        ReplayBackend { inner, record, curr_frame : 0 }
+    }
+}
+
+impl Backend for ReplayBackend {
+    fn get_events(&mut self) -> Vec<(Key, EventType)> {
+        // Keep the wrapped backend responsive (window events, etc.).
+        self.inner.get_events();
+
+        match self.record.frames.get(self.curr_frame) {
+            Some(frame) => { frame.events.clone() }
+            None => { Vec::new() }
+        }
+    }
+
+    fn should_close(&self) -> bool { self.curr_frame >= self.record.frames.len() || self.inner.should_close() }
+
+    fn get_time(&self) -> f64 {
+        match self.record.frames.get(self.curr_frame) {
+            Some(frame) => { frame.delta_time }
+            None => { 0.0 }
+        }
+    }
+
+    fn set_time(&mut self, _time : f64) { }
+
+    fn present(&mut self, image : RgbImage) -> Result<(), String> {
+        self.inner.present(image)?;
+        self.curr_frame += 1;
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
-use crate::image::{RgbImage, Rgb};
+use crate::image::RgbImage;
 use super::camera::Camera;
+use super::common::Math;
 
 mod road;
 pub use road::*;
@@ -7,11 +8,17 @@ pub use road::*;
 mod track_data;
 pub use track_data::*;
 
+const GROUND_MARCH_START : f32 = 0.05;
+// Multiplier, so the step grows with the distance as rows get denser.
+const GROUND_MARCH_STEP : f32 = 1.005;
+
 #[derive(Clone, Copy)]
 pub struct YData {
     pub distance : f32,
     pub norm_road_offset : f32,
     pub road_scale : f32,
+    // Ground height relative to the road under the camera.
+    pub height : f32,
     pub is_visible : bool,
     pub is_horz_line : bool
 }
@@ -19,10 +26,7 @@ pub struct YData {
 pub struct Track {
     pub data : TrackData,
     lines_density : f32,
-    pub y_data : Vec<YData>,
-
-    ground_color_main : Rgb<u8>,
-    ground_color_secondary : Rgb<u8>
+    pub y_data : Vec<YData>
 }
 
 impl Track {
@@ -30,19 +34,13 @@ impl Track {
         Track { 
             data,
             lines_density : 0.5,
-            ground_color_main : Rgb([0, 100, 0]), 
-            ground_color_secondary : Rgb([0, 120, 0]),
             y_data : Vec::new()
         }
     }
 
     pub fn compute_y_data(&mut self, camera : &Camera, frame_height : u32) {
-        let mut hill_width_multiplier = 1.0;
         self.y_data.clear();
 
-        let mut pitch = camera.pitch;
-        pitch += self.data.get_camera_pitch_delta(camera.road_distance);
-
         let mut offset_delta = 0.0;
         let mut prev_norm_offset = 0.0;
         let mut global_offset = 0.0;
@@ -52,13 +50,7 @@ impl Track {
         let mut road_lines_accum = camera.road_distance % (2.0 * self.lines_density);
         let mut is_horz_line = false;
 
-        for y in 0..frame_height {
-            // Visible road segment distance.
-            let y_norm = (y as f32) / (frame_height as f32);
-            let screen_point_ground_height = camera.y_pos - (1.0 - y_norm * pitch) * camera.viewport_height;
-            let mut vis_road_dist = screen_point_ground_height * camera.screen_dist / (camera.y_pos - screen_point_ground_height);
-            vis_road_dist += camera.screen_dist;
-
+        for (vis_road_dist, height) in self.get_visible_ground(camera, frame_height) {
             // Horz lines. 
             if prev_y_vis_road_dist != 0.0 { 
                 let segment_length = vis_road_dist - prev_y_vis_road_dist;
@@ -71,10 +63,8 @@ impl Track {
             
             prev_y_vis_road_dist = vis_road_dist;
 
-            // Occlusion culling.
-            if vis_road_dist > camera.far_plane || vis_road_dist < 0.0 { continue; }
             if !self.data.is_visible(vis_road_dist + camera.road_distance) { 
-                self.y_data.push(YData { distance : vis_road_dist, norm_road_offset : 0.0, road_scale : 0.0, is_visible : false, is_horz_line });
+                self.y_data.push(YData { distance : vis_road_dist, norm_road_offset : 0.0, road_scale : 0.0, height, is_visible : false, is_horz_line });
                 continue; 
             }
 
@@ -100,16 +90,42 @@ impl Track {
 
             norm_road_offset -= camera.x_offset * camera.screen_dist / vis_road_dist;
 
-            // Hills.
-            hill_width_multiplier += self.data.get_hill_width_multiplier_delta(vis_road_dist + camera.road_distance);
-
             // Road width.
-            let road_scale = hill_width_multiplier * camera.screen_dist / vis_road_dist;
+            let road_scale = camera.screen_dist / vis_road_dist;
 
-            self.y_data.push(YData { distance : vis_road_dist, norm_road_offset : norm_road_offset, road_scale, is_visible : true, is_horz_line });
+            self.y_data.push(YData { distance : vis_road_dist, norm_road_offset : norm_road_offset, road_scale, height, is_visible : true, is_horz_line });
         }
     }
 
+    // Distance and height of the ground seen at every screen row from the bottom. Ground is marched from the camera
+    // and each row takes the nearest point projected onto it, so everything behind crests is occluded.
+    fn get_visible_ground(&self, camera : &Camera, frame_height : u32) -> Vec<(f32, f32)> {
+        let mut ground = Vec::with_capacity(frame_height as usize);
+
+        let camera_height = self.data.get_height(camera.road_distance);
+        let get_height = |distance : f32| self.data.get_height(distance + camera.road_distance) - camera_height;
+
+        let mut prev_distance = GROUND_MARCH_START;
+        let mut prev_y = camera.get_screen_y(prev_distance, get_height(prev_distance), frame_height);
+        let mut distance = prev_distance;
+        while distance < camera.far_plane && ground.len() < frame_height as usize {
+            distance = Math::min(distance * GROUND_MARCH_STEP, camera.far_plane);
+            let y = camera.get_screen_y(distance, get_height(distance), frame_height);
+
+            // Rows that are already taken by the nearer ground are occluded.
+            while (ground.len() as f32) <= y && ground.len() < frame_height as usize {
+                let t = (ground.len() as f32 - prev_y) / (y - prev_y);
+                let row_distance = Math::lerp(prev_distance, distance, t);
+                ground.push((row_distance, get_height(row_distance)));
+            }
+
+            prev_distance = distance;
+            prev_y = y;
+        }
+
+        ground
+    }
+
     pub fn get_horz_speed(&self, camera : &Camera) -> f32 {
         let offset = self.data.get_segment_offset(camera.road_distance, camera.road_distance + camera.screen_dist);
         match offset {
@@ -137,9 +153,9 @@ impl Track {
         // Render ground.
         for y in 0..self.y_data.len() {
             let ground_color = if self.y_data[y].is_horz_line { 
-                self.ground_color_main
+                self.data.ground_colors.0
             } else { 
-                self.ground_color_secondary 
+                self.data.ground_colors.1 
             };
             for x in 0..image.width() { image.put_pixel(x, y as u32, ground_color); }
         }
@@ -19,11 +19,33 @@ impl KeyPoint {
     }
 }
 
+// Lane direction relative to the player's direction of travel.
+#[derive(Clone, Copy, PartialEq)]
+pub enum LaneDirection {
+    Forward,
+    Backward
+}
+
+impl LaneDirection {
+    pub fn opposite(&self) -> LaneDirection {
+        match self {
+            LaneDirection::Forward => { LaneDirection::Backward }
+            LaneDirection::Backward => { LaneDirection::Forward }
+        }
+    }
+}
+
+const MARKING_WIDTH : f32 = 0.02;
+const EDGE_LINE_INSET : f32 = 0.04;
+const MARKING_COLOR : Rgb<u8> = Rgb([230, 230, 230]);
+const CENTER_LINE_COLOR : Rgb<u8> = Rgb([255, 200, 0]);
+
 #[derive(Clone)]
 pub struct Road {
     start : f32,
     end : f32,
     width : f32,
+    lanes : Vec<LaneDirection>, // From left to right.
     
     keypoints : Vec<KeyPoint>, // Sorted.
     
@@ -31,18 +53,66 @@ pub struct Road {
 }
 
 impl Road {
-    pub fn new(width : f32, mut keypoints : Vec<KeyPoint>, texture : Rc<RgbImage>) -> Road {
+    pub fn new(width : f32, lanes : Vec<LaneDirection>, mut keypoints : Vec<KeyPoint>, texture : Rc<RgbImage>) -> Road {
         keypoints.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
 
         Road { 
             start : keypoints[0].distance, 
             end : keypoints.last().unwrap().distance, 
             width, 
+            lanes,
             keypoints, 
             texture 
         }
     }
 
+    // Left and right sides are swapped when the road is driven backwards.
+    pub fn reversed(&self, length : f32) -> Road {
+        let keypoints = self.keypoints.iter().map(|keypoint| KeyPoint::new(length - keypoint.distance, -keypoint.offset)).collect();
+        let lanes = self.lanes.iter().rev().map(|lane| lane.opposite()).collect();
+        Road::new(self.width, lanes, keypoints, self.texture.clone())
+    }
+
+    pub fn get_width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn get_lane_count(&self) -> usize {
+        self.lanes.len()
+    }
+
+    pub fn get_lane_direction(&self, lane : usize) -> LaneDirection {
+        self.lanes[lane]
+    }
+
+    // Offset of the lane center.
+    pub fn get_lane_offset(&self, lane : usize, road_distance : f32) -> Option<f32> {
+        let offset = self.get_segment_offset(road_distance);
+        if offset.is_none() { return None; }
+
+        let lane_width = self.width / self.lanes.len() as f32;
+        Some(offset.unwrap() - self.width * 0.5 + lane_width * (lane as f32 + 0.5))
+    }
+
+    // Closest lane going in the direction, if there is any.
+    pub fn get_closest_lane(&self, x_pos : f32, road_distance : f32, direction : LaneDirection) -> Option<usize> {
+        let mut closest_lane = None;
+        let mut closest_lane_dist = std::f32::INFINITY;
+        for lane in 0..self.lanes.len() {
+            if self.lanes[lane] != direction { continue; }
+            let lane_offset = self.get_lane_offset(lane, road_distance);
+            if lane_offset.is_none() { return None; }
+
+            let dist = (lane_offset.unwrap() - x_pos).abs();
+            if dist < closest_lane_dist {
+                closest_lane_dist = dist;
+                closest_lane = Some(lane);
+            }
+        }
+
+        closest_lane
+    }
+
     pub fn roadside_dist(&self, car_left : f32, car_right : f32, car_road_dist : f32) -> Option<f32> {
         let offset = self.get_segment_offset(car_road_dist);
         if offset.is_none() { return None; }
@@ -71,6 +141,7 @@ impl Road {
         None
     }
 
+    // Each row holds the nearest visible ground, so parts of the road behind crests are never drawn.
     pub fn render_from_y_data(&self, image : &mut RgbImage, y_data : &Vec<YData>, camera : &Camera) {
         for y in 0..y_data.len() as u32 {
             let y_data = y_data[y as usize];
@@ -91,18 +162,46 @@ impl Road {
             let road_width_px = (right_border_px - left_border_px + 1) as u32;
 
             // Render main texture if there is horz line, secondary texture elsewhere.
-            if road_width_px < self.texture.width() - 1 {              
-                let mut road_tex_sample_x = if y_data.is_horz_line { 0 } else { self.texture.width() - road_width_px };
-                let mut road_tex_sample_y = if y_data.is_horz_line { self.texture.height() - road_width_px } else { road_width_px - 1 };
-                road_tex_sample_y = self.texture.height() - road_tex_sample_y - 1;
-
-                for x in left_border_px..right_border_px + 1{
-                    let tex_pixel = self.texture.get_pixel(road_tex_sample_x, road_tex_sample_y);
-                    road_tex_sample_x += 1;
-                    if x < 0 || x >= image.width() as i32{ continue; }
-                    image.put_pixel(x as u32, y, *tex_pixel);
-                }
+            // Texture has a row for every road width up to its size, wider roads are stretched from the widest row.
+            let sample_width = Math::min(road_width_px, self.texture.width() - 2);
+            let road_tex_sample_start_x = if y_data.is_horz_line { 0 } else { self.texture.width() - sample_width };
+            let mut road_tex_sample_y = if y_data.is_horz_line { self.texture.height() - sample_width } else { sample_width - 1 };
+            road_tex_sample_y = self.texture.height() - road_tex_sample_y - 1;
+
+            for x in Math::max(left_border_px, 0)..Math::min(right_border_px + 1, image.width() as i32) {
+                let road_tex_sample_x = road_tex_sample_start_x + (x - left_border_px) as u32 * sample_width / road_width_px;
+                let tex_pixel = self.texture.get_pixel(road_tex_sample_x, road_tex_sample_y);
+                image.put_pixel(x as u32, y, *tex_pixel);
+            }
+
+            self.render_markings(image, y, norm_left_border, road_width, y_data.is_horz_line);
+        }
+    }
+
+    fn render_markings(&self, image : &mut RgbImage, y : u32, norm_left_border : f32, road_width : f32, is_horz_line : bool) {
+        let scale = road_width / self.width;
+        let marking_width_px = Math::max(1, (MARKING_WIDTH * scale * image.width() as f32) as i32);
+        let lane_width = road_width / self.lanes.len() as f32;
+
+        let mut draw_marking = |norm_x : f32, color : Rgb<u8>| {
+            let left_px = (norm_x * image.width() as f32) as i32 - marking_width_px / 2;
+            for x in Math::max(left_px, 0)..Math::min(left_px + marking_width_px, image.width() as i32) {
+                image.put_pixel(x as u32, y, color);
+            }
+        };
+
+        // Edge lines.
+        draw_marking(norm_left_border + EDGE_LINE_INSET * scale, MARKING_COLOR);
+        draw_marking(norm_left_border + road_width - EDGE_LINE_INSET * scale, MARKING_COLOR);
+
+        // Solid line between opposite directions, dashed between lanes of the same one.
+        for lane in 1..self.lanes.len() {
+            let norm_x = norm_left_border + lane_width * lane as f32;
+            if self.lanes[lane - 1] != self.lanes[lane] {
+                draw_marking(norm_x, CENTER_LINE_COLOR);
+            } else if !is_horz_line {
+                draw_marking(norm_x, MARKING_COLOR);
             }
         }
     }
-}
\ No newline at end of file
+}
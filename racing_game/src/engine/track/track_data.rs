@@ -1,24 +1,33 @@
+use image::Rgb;
+
 use crate::engine::common::*;
 use super::road::Road;
 
+// Crest if height is positive, dip otherwise.
 #[derive(Clone)]
 pub struct Heel{
     start : f32,
     end : f32,
-    start_steepness : f32,
-    end_steepness : f32
+    height : f32
 }
 
 impl Heel {
-    pub fn new(start : f32, end : f32, start_steepness : f32, end_steepness : f32) -> Heel {
-        Heel { start, end, start_steepness, end_steepness }
+    pub fn new(start : f32, end : f32, height : f32) -> Heel {
+        Heel { start, end, height }
     }
 
-    fn get_width_multiplier(&self, distance : f32) -> Option<f32> {
+    fn get_height(&self, distance : f32) -> Option<f32> {
         if distance < self.start || distance > self.end { return None; }
 
         let t = (distance - self.start) / (self.end - self.start);
-        return Some(Math::lerp(self.start_steepness, self.end_steepness, t));
+        // Rise on the first half and fall on the second one, so there are no breaks at the ends.
+        let t = if t < 0.5 { t * 2.0 } else { (1.0 - t) * 2.0 };
+        return Some(Math::smoothstep(0.0, self.height, t));
+    }
+
+    // Heel is symmetric, so only its ends are moved.
+    fn reversed(&self, length : f32) -> Heel {
+        Heel { start : length - self.end, end : length - self.start, height : self.height }
     }
 }
 
@@ -29,12 +38,21 @@ pub struct Curvature {
     pub strength : f32
 }
 
+impl Curvature {
+    // Left turn becomes the right one when driven backwards.
+    fn reversed(&self, length : f32) -> Curvature {
+        Curvature { start : length - self.end, end : length - self.start, strength : -self.strength }
+    }
+}
+
 #[derive(Clone)]
 pub struct TrackData {
     length : f32,
     heels : Vec<Heel>,
     curvatures : Vec<Curvature>,
-    pub roads : Vec<Road>
+    pub roads : Vec<Road>,
+    // Ground stripes, main one is under the horizontal lines.
+    pub ground_colors : (Rgb<u8>, Rgb<u8>)
 }
 
 pub enum OffsetMode {
@@ -43,8 +61,19 @@ pub enum OffsetMode {
 }
 
 impl TrackData {
-    pub fn new(length : f32, curvatures : Vec<Curvature>, heels : Vec<Heel>, roads : Vec<Road>) -> TrackData {
-        TrackData { length, heels, curvatures, roads }
+    pub fn new(length : f32, curvatures : Vec<Curvature>, heels : Vec<Heel>, roads : Vec<Road>, ground_colors : (Rgb<u8>, Rgb<u8>)) -> TrackData {
+        TrackData { length, heels, curvatures, roads, ground_colors }
+    }
+
+    // Same track as seen when driving from its end to its start.
+    pub fn reversed(&self) -> TrackData {
+        TrackData {
+            length : self.length,
+            heels : self.heels.iter().rev().map(|heel| heel.reversed(self.length)).collect(),
+            curvatures : self.curvatures.iter().rev().map(|curvature| curvature.reversed(self.length)).collect(),
+            roads : self.roads.iter().map(|road| road.reversed(self.length)).collect(),
+            ground_colors : self.ground_colors
+        }
     }
 
     pub fn get_segment_offset(&self, camera_road_distance : f32, road_distance : f32) -> OffsetMode {
@@ -58,21 +87,38 @@ impl TrackData {
         return OffsetMode::AsIs;
     }
 
-    pub fn get_hill_width_multiplier_delta(&self, road_distance : f32) -> f32 {
+    // Strength of the curvature, negative turns left.
+    pub fn get_curvature(&self, road_distance : f32) -> f32 {
+        self.curvatures.iter()
+        .find(|curvature| curvature.start < road_distance && curvature.end > road_distance)
+        .map_or(0.0, |curvature| curvature.strength)
+    }
+
+    pub fn get_height(&self, road_distance : f32) -> f32 {
         for heel in &self.heels {
-            if let Some(mul) = heel.get_width_multiplier(road_distance) {
-                return mul;
+            if let Some(height) = heel.get_height(road_distance) {
+                return height;
             }
         }
         
         0.0
     }
 
-    pub fn is_visible(&self, road_distance : f32) -> bool {
-        self.length >= road_distance
+    // Borders of the widest road surface at the distance, if there is any road.
+    pub fn get_road_borders(&self, road_distance : f32) -> Option<(f32, f32)> {
+        self.roads.iter()
+        .filter_map(|road| road.get_segment_offset(road_distance).map(|offset| (offset - road.get_width() * 0.5, offset + road.get_width() * 0.5)))
+        .fold(None, |borders, (left, right)| match borders {
+            Some((min_left, max_right)) => { Some((Math::min(min_left, left), Math::max(max_right, right))) }
+            None => { Some((left, right)) }
+        })
     }
 
-    pub fn get_camera_pitch_delta(&self, camera_road_dist : f32) -> f32 {
-        return self.get_hill_width_multiplier_delta(camera_road_dist) * 50.0;
+    pub fn get_length(&self) -> f32 {
+        self.length
+    }
+
+    pub fn is_visible(&self, road_distance : f32) -> bool {
+        self.length >= road_distance
     }
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+use std::ops::Range;
 use std::rc::Rc;
 
 use super::common::Math;
@@ -5,96 +6,710 @@ use super::billboards::*;
 use super::camera::*;
 use super::track::*;
 
-#[derive(Clone)]
+// Following distance is the minimal gap plus the distance covered during the headway time.
+const MIN_GAP : f32 = 0.5;
+// Headway time of calm and hasty drivers.
+const HEADWAY_TIME : (f32, f32) = (1.2, 0.5);
+// Braking car ahead is followed from farther away.
+const BRAKING_LEADER_GAP_FACTOR : f32 = 1.5;
+const ACCELERATION : f32 = 1.0;
+const DECELERATION : f32 = 1.5;
+const BRAKE_DECELERATION : f32 = 4.0;
+// Cars farther ahead don't affect the speed.
+const LOOK_AHEAD_DISTANCE : f32 = 10.0;
+// Oncoming lane has to be free that far ahead to overtake on it.
+const ONCOMING_CLEARANCE : f32 = 40.0;
+// Overtaking car returns to its lane this far ahead of the overtaken car.
+const MERGE_GAP : f32 = 1.0;
+// Time stuck behind the player before honking, for calm and hasty drivers.
+const HONK_PATIENCE : (f32, f32) = (5.0, 1.5);
+// Car is stuck when it goes slower than this share of its cruise speed.
+const STUCK_SPEED_FACTOR : f32 = 0.5;
+// Oncoming car flashes its headlights at the player driving in its lane.
+const FLASH_DISTANCE : f32 = 30.0;
+// Driver doesn't honk or flash more often.
+const SIGNAL_COOLDOWN : f32 = 5.0;
+
+#[derive(Clone, PartialEq)]
 pub struct TrafficCar {
     billboard_id : BillboardId,
     billboard : Option<Billboard>,
+    // Speeds are negative for cars going against the player.
     speed : f32,
+    cruise_speed : f32,
     steer_speed : f32,
     width : f32,
     road_distance : f32,
     x_pos : f32,
-    sleeping : bool
+    lane : Option<usize>,
+    braking : bool,
+    // Zero for the calmest driver, one for the hastiest.
+    temper : f32,
+    stuck_time : f32,
+    signal_cooldown : f32
 }
 
 impl TrafficCar {
-    pub fn new(billboard : Billboard, width : f32, speed : f32, steer_speed : f32) -> TrafficCar {
+    pub fn new(billboard : Billboard, width : f32, speed : f32, steer_speed : f32, temper : f32) -> TrafficCar {
         TrafficCar {
             billboard_id : BillboardId(0),
             speed,
+            cruise_speed : speed,
             steer_speed,
             width,
             road_distance : billboard.road_distance,
             x_pos : billboard.offset,
-            billboard : Some(billboard),
-            sleeping : true
+            lane : None,
+            braking : false,
+            temper,
+            stuck_time : 0.0,
+            signal_cooldown : 0.0,
+            billboard : Some(billboard)
+        }
+    }
+
+    // Car goes the opposite way relative to the player driving the track backwards.
+    fn reversed(&self, length : f32) -> TrafficCar {
+        TrafficCar {
+            billboard_id : self.billboard_id,
+            billboard : self.billboard.as_ref().map(|billboard| billboard.reversed(length)),
+            speed : -self.speed,
+            cruise_speed : -self.cruise_speed,
+            steer_speed : self.steer_speed,
+            width : self.width,
+            road_distance : length - self.road_distance,
+            x_pos : -self.x_pos,
+            lane : None,
+            braking : self.braking,
+            temper : self.temper,
+            stuck_time : self.stuck_time,
+            signal_cooldown : self.signal_cooldown
         }
     }
+
+    // Stopped car keeps the direction of its lane.
+    fn get_direction(&self) -> LaneDirection {
+        if self.cruise_speed >= 0.0 { LaneDirection::Forward } else { LaneDirection::Backward }
+    }
+
+    fn get_direction_sign(&self) -> f32 {
+        if self.cruise_speed >= 0.0 { 1.0 } else { -1.0 }
+    }
+
+    fn overlaps(&self, x_pos : f32, width : f32) -> bool {
+        (x_pos - self.x_pos).abs() < (width + self.width) * 0.5
+    }
+}
+
+// Player's car as the traffic sees it.
+#[derive(Clone, Copy)]
+pub struct TrafficPlayer {
+    pub road_distance : f32,
+    pub x_pos : f32,
+    pub width : f32,
+    pub speed : f32,
+    pub braking : bool
+}
+
+// Signals the drivers give to the player.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TrafficEvent {
+    Honk,
+    FlashHeadlights
 }
 
+// Closest car ahead in the lane, the speed is along the direction of the following car.
+struct Leader {
+    distance : f32,
+    speed : f32,
+    braking : bool,
+    is_player : bool
+}
+
+// Creates the cars of one cell of the road when the camera comes close to it.
+// Cells are in the coordinates of the road as it was generated, whatever direction it is driven in.
+pub trait TrafficSpawner {
+    fn spawn(&self, cell : usize, start : f32, end : f32) -> Vec<TrafficCar>;
+}
+
+// Cars live only around the camera, so long roads don't keep all their traffic.
+const SPAWN_CELL_LENGTH : f32 = 20.0;
+const DESPAWN_DISTANCE_BEHIND : f32 = 20.0;
+const DESPAWN_DISTANCE_AHEAD : f32 = 40.0;
+// Cars don't appear right around the player.
+const SPAWN_CLEARANCE : f32 = 10.0;
+
 #[derive(Clone)]
 pub struct Traffic {
     cars : Vec<TrafficCar>,
-    track : Option<Rc<Track>>,
-    overtake_distance : f32
+    overtake_distance : f32,
+    spawner : Option<Rc<dyn TrafficSpawner>>,
+    reversed : bool,
+    // Cells numbered from the road start that weren't spawned yet, the camera never goes back,
+    // so driving forward takes them from the range start and driving backwards from its end.
+    unspawned_cells : Option<Range<usize>>
 }
 
 impl Traffic {
     pub fn new(overtake_distance : f32) -> Traffic {
-        Traffic { cars : Vec::new(), track : None, overtake_distance }
+        Traffic { cars : Vec::new(), overtake_distance, spawner : None, reversed : false, unspawned_cells : None }
     }
 
-    pub fn set_track(&mut self, track : Rc<Track>) {
-        self.track = Some(track);
+    pub fn set_spawner(&mut self, spawner : Rc<dyn TrafficSpawner>) {
+        self.spawner = Some(spawner);
+    }
+
+    pub fn reversed(&self, length : f32) -> Traffic {
+        Traffic {
+            cars : self.cars.iter().map(|car| car.reversed(length)).collect(),
+            overtake_distance : self.overtake_distance,
+            spawner : self.spawner.clone(),
+            reversed : !self.reversed,
+            unspawned_cells : self.unspawned_cells.clone()
+        }
     }
 
     pub fn add_car(&mut self, billboards : &mut Billboards, mut car : TrafficCar) {  
         let billboard = car.billboard.take().unwrap(); 
-        let road_distance = billboard.road_distance; 
         car.billboard_id = billboards.add_dynamic(billboard);
         self.cars.push(car);
     }
 
-    pub fn update(&mut self, camera : &Camera, delta_time : f32, billboards : &mut Billboards) {
+    fn spawn_cars(&mut self, track : &Track, camera : &Camera, billboards : &mut Billboards) {
+        let spawner = match &self.spawner {
+            Some(spawner) => { spawner.clone() }
+            None => { return; }
+        };
+
+        let length = track.data.get_length();
+        let cell_count = (length / SPAWN_CELL_LENGTH).ceil() as usize;
+        let spawn_start = camera.road_distance - DESPAWN_DISTANCE_BEHIND;
+        let spawn_end = camera.road_distance + camera.far_plane;
+        let mut unspawned_cells = self.unspawned_cells.clone().unwrap_or(0..cell_count);
+        while !unspawned_cells.is_empty() {
+            let cell = if self.reversed { unspawned_cells.end - 1 } else { unspawned_cells.start };
+            let (start, end) = (cell as f32 * SPAWN_CELL_LENGTH, Math::min((cell + 1) as f32 * SPAWN_CELL_LENGTH, length));
+            let (driving_start, driving_end) = if self.reversed { (length - end, length - start) } else { (start, end) };
+            if driving_start > spawn_end { break; }
+            if self.reversed { unspawned_cells.end -= 1; } else { unspawned_cells.start += 1; }
+            // Cells the camera has already passed stay empty.
+            if driving_end < spawn_start { continue; }
+
+            for car in spawner.spawn(cell, start, end) {
+                let mut car = if self.reversed { car.reversed(length) } else { car };
+                if (car.road_distance - camera.road_distance).abs() < SPAWN_CLEARANCE { continue; }
+                // New cars appear in the middle of their lanes.
+                if let Some(road) = Self::get_closest_road(track, car.x_pos, car.road_distance) {
+                    car.lane = road.get_closest_lane(car.x_pos, car.road_distance, car.get_direction());
+                    if let Some(lane_offset) = car.lane.and_then(|lane| road.get_lane_offset(lane, car.road_distance)) {
+                        car.x_pos = lane_offset;
+                        car.billboard.as_mut().unwrap().offset = lane_offset;
+                    }
+                }
+                self.add_car(billboards, car);
+            }
+        }
+        self.unspawned_cells = Some(unspawned_cells);
+    }
+
+    fn despawn_cars(&mut self, camera : &Camera, billboards : &mut Billboards) {
+        let min_distance = camera.road_distance - DESPAWN_DISTANCE_BEHIND;
+        let max_distance = camera.road_distance + camera.far_plane + DESPAWN_DISTANCE_AHEAD;
+        let (cars, despawned) : (Vec<_>, Vec<_>) = self.cars.drain(..)
+        .partition(|car| car.road_distance >= min_distance && car.road_distance <= max_distance);
+        self.cars = cars;
+
+        for car in despawned { billboards.remove_dynamic(car.billboard_id); }
+    }
+
+    fn get_closest_road(track : &Track, x_pos : f32, road_distance : f32) -> Option<&Road> {
+        let mut closest_road = None;
+        let mut closest_road_dist = std::f32::INFINITY;
+        for road in &track.data.roads {
+            let road_offset = road.get_segment_offset(road_distance);
+            if road_offset.is_none() { continue; }
+
+            let dist_to_road = (road_offset.unwrap() - x_pos).abs();
+            if closest_road_dist > dist_to_road { 
+                closest_road_dist = dist_to_road;
+                closest_road = Some(road);
+            }
+        }
+
+        closest_road
+    }
+
+    // Cars follow each other and the player, overtake slower ones and signal to the player.
+    // The update has no randomness, so the same spawned cars always drive the same way.
+    pub fn update(&mut self, track : &Track, camera : &Camera, player : &TrafficPlayer, delta_time : f32, billboards : &mut Billboards) -> Vec<TrafficEvent> {
+        self.spawn_cars(track, camera, billboards);
+        self.despawn_cars(camera, billboards);
+
+        let mut events = Vec::new();
         for i in 0..self.cars.len() {
-            if !self.cars[i].sleeping {
-                let car_x = self.cars[i].x_pos;
+            let car_x = self.cars[i].x_pos;
+            let car_road_distance = self.cars[i].road_distance;
+            let direction = self.cars[i].get_direction();
 
-                let mut closest_road = &self.track.as_ref().unwrap().data.roads[0];
-                let mut closest_road_dist = std::f32::INFINITY;
+            // Car off every road waits where it is.
+            let closest_road = match Self::get_closest_road(track, car_x, car_road_distance) {
+                Some(road) => { road }
+                None => { continue; }
+            };
 
-                for road in &self.track.as_ref().unwrap().data.roads {
-                    let road_offset = road.get_segment_offset(self.cars[i].road_distance);
-                    if road_offset.is_none() { continue; }
+            let lane = match self.cars[i].lane {
+                Some(lane) if lane < closest_road.get_lane_count() => { Some(lane) }
+                _ => { closest_road.get_closest_lane(car_x, car_road_distance, direction) }
+            };
+            self.cars[i].lane = lane;
 
-                    let dist_to_road = (road_offset.unwrap() - car_x).abs();
-                    if closest_road_dist > dist_to_road { 
-                        closest_road_dist = dist_to_road;
-                        closest_road = road;
+            let overtaking = lane.is_some_and(|lane| closest_road.get_lane_direction(lane) != direction);
+            let leader = self.get_leader(i, overtaking, player);
+            if let Some(lane) = lane {
+                if overtaking {
+                    // Overtaking car goes back to its lane as soon as there is room.
+                    if let Some(own_lane) = closest_road.get_closest_lane(car_x, car_road_distance, direction) {
+                        if self.is_lane_free(i, closest_road, own_lane, MERGE_GAP, self.overtake_distance, player) { self.cars[i].lane = Some(own_lane); }
+                    }
+                } else if let Some(leader) = &leader {
+                    if leader.distance < self.overtake_distance && leader.speed < self.cars[i].cruise_speed.abs() {
+                        if let Some(overtake_lane) = self.get_overtake_lane(i, closest_road, lane, player) { self.cars[i].lane = Some(overtake_lane); }
                     }
                 }
 
-                let car_width = self.cars[i].width;
-                let car_left = car_x - car_width * 0.5;
-                let car_right = car_x + car_width * 0.5;
-                let roadside_dist = closest_road.roadside_dist(car_left, car_right, self.cars[i].road_distance);
-                if let Some(roadside_dist) = roadside_dist {
-                    self.cars[i].x_pos += self.cars[i].steer_speed * delta_time * if roadside_dist > 0.0 { -1.0 } else { 1.0 };
+                if let Some(lane_offset) = closest_road.get_lane_offset(self.cars[i].lane.unwrap(), car_road_distance) {
+                    let steer = self.cars[i].steer_speed * delta_time;
+                    self.cars[i].x_pos += Math::max(-steer, Math::min(steer, lane_offset - car_x));
                 }
+            }
 
-                self.cars[i].road_distance += self.cars[i].speed * delta_time;
+            self.follow(i, leader.as_ref(), delta_time);
+            if let Some(event) = self.signal(i, leader.as_ref(), player, delta_time) { events.push(event); }
 
-                let car_billboard = billboards.get_dynamic_mut(self.cars[i].billboard_id);
-                car_billboard.road_distance = self.cars[i].road_distance;
-                car_billboard.offset = self.cars[i].x_pos;
-            } else {
-                if self.cars[i].speed > 0.0 && self.cars[i].road_distance <= camera.road_distance { 
-                    self.cars[i].sleeping = false; 
-                }
-                if self.cars[i].speed < 0.0 && self.cars[i].road_distance <= camera.road_distance + camera.far_plane { 
-                    self.cars[i].sleeping = false; 
-                }
+            let car = &mut self.cars[i];
+            car.road_distance += car.speed * delta_time;
+
+            let car_billboard = billboards.get_dynamic_mut(car.billboard_id);
+            car_billboard.road_distance = car.road_distance;
+            car_billboard.offset = car.x_pos;
+        }
+
+        events
+    }
+
+    // Car speeds up to the cruise speed unless it has to keep the following distance to the leader.
+    fn follow(&mut self, car_id : usize, leader : Option<&Leader>, delta_time : f32) {
+        let car = &mut self.cars[car_id];
+        let speed = car.speed.abs();
+        let cruise_speed = car.cruise_speed.abs();
+        let headway_time = Math::lerp(HEADWAY_TIME.0, HEADWAY_TIME.1, car.temper);
+
+        let (target_speed, braking) = match leader {
+            Some(leader) => {
+                let gap_factor = if leader.braking { BRAKING_LEADER_GAP_FACTOR } else { 1.0 };
+                let safe_gap = MIN_GAP + speed * headway_time * gap_factor;
+                // Speed that closes the distance to the safe gap within the headway time.
+                let target_speed = Math::min(Math::max(leader.speed + (leader.distance - safe_gap) / headway_time, 0.0), cruise_speed);
+                (target_speed, target_speed < speed && (leader.distance < safe_gap || leader.braking))
             }
+            None => { (cruise_speed, false) }
+        };
+
+        let speed = if target_speed > speed {
+            Math::min(speed + ACCELERATION * delta_time, target_speed)
+        } else {
+            let deceleration = if braking { BRAKE_DECELERATION } else { DECELERATION };
+            Math::max(speed - deceleration * delta_time, target_speed)
+        };
+
+        car.speed = speed * car.get_direction_sign();
+        car.braking = braking;
+    }
+
+    // Driver stuck behind the player honks, oncoming driver flashes the headlights at the player in its lane.
+    fn signal(&mut self, car_id : usize, leader : Option<&Leader>, player : &TrafficPlayer, delta_time : f32) -> Option<TrafficEvent> {
+        let car = &mut self.cars[car_id];
+        car.signal_cooldown = Math::max(car.signal_cooldown - delta_time, 0.0);
+
+        let behind_player = leader.is_some_and(|leader| leader.is_player);
+        let is_stuck = behind_player && car.speed.abs() < car.cruise_speed.abs() * STUCK_SPEED_FACTOR;
+        car.stuck_time = if is_stuck { car.stuck_time + delta_time } else { 0.0 };
+        if car.signal_cooldown > 0.0 { return None; }
+
+        let event = if car.stuck_time > Math::lerp(HONK_PATIENCE.0, HONK_PATIENCE.1, car.temper) {
+            car.stuck_time = 0.0;
+            Some(TrafficEvent::Honk)
+        } else {
+            let distance_to_player = (player.road_distance - car.road_distance) * car.get_direction_sign();
+            let player_ahead = distance_to_player > 0.0 && distance_to_player < FLASH_DISTANCE;
+            let is_oncoming = car.get_direction() == LaneDirection::Backward;
+            if is_oncoming && player_ahead && car.overlaps(player.x_pos, player.width) { Some(TrafficEvent::FlashHeadlights) } else { None }
+        };
+
+        if event.is_some() { car.signal_cooldown = SIGNAL_COOLDOWN; }
+        event
+    }
+
+    // Closest car or the player ahead within the look ahead distance, oncoming cars matter only while overtaking.
+    fn get_leader(&self, car_id : usize, overtaking : bool, player : &TrafficPlayer) -> Option<Leader> {
+        let car = &self.cars[car_id];
+        let sign = car.get_direction_sign();
+
+        let cars = self.cars.iter().enumerate()
+        .filter(|(i, other)| *i != car_id && (overtaking || other.get_direction() == car.get_direction()) && car.overlaps(other.x_pos, other.width))
+        .map(|(_, other)| Leader { distance : (other.road_distance - car.road_distance) * sign, speed : other.speed * sign, braking : other.braking, is_player : false });
+
+        let player_leader = if car.overlaps(player.x_pos, player.width) {
+            Some(Leader { distance : (player.road_distance - car.road_distance) * sign, speed : player.speed * sign, braking : player.braking, is_player : true })
+        } else {
+            None
+        };
+
+        cars.chain(player_leader)
+        .filter(|leader| leader.distance > 0.0 && leader.distance < LOOK_AHEAD_DISTANCE)
+        .min_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap())
+    }
+
+    // Neighbour lane of the same direction without cars around, otherwise the oncoming lane when it is free far ahead.
+    fn get_overtake_lane(&self, car_id : usize, road : &Road, lane : usize, player : &TrafficPlayer) -> Option<usize> {
+        let car = &self.cars[car_id];
+
+        let neighbour_lanes : Vec<usize> = [lane.checked_sub(1), Some(lane + 1)].iter().flatten().cloned().filter(|neighbour_lane| *neighbour_lane < road.get_lane_count()).collect();
+        let same_direction_lane = neighbour_lanes.iter().cloned().find(|neighbour_lane| {
+            road.get_lane_direction(*neighbour_lane) == car.get_direction() &&
+            self.is_lane_free(car_id, road, *neighbour_lane, self.overtake_distance, self.overtake_distance, player)
+        });
+        if same_direction_lane.is_some() { return same_direction_lane; }
+
+        neighbour_lanes.into_iter().find(|neighbour_lane| {
+            road.get_lane_direction(*neighbour_lane) != car.get_direction() &&
+            self.is_lane_free(car_id, road, *neighbour_lane, self.overtake_distance, ONCOMING_CLEARANCE, player)
+        })
+    }
+
+    // No car and no player in the lane from the distance behind to the distance ahead of the car.
+    fn is_lane_free(&self, car_id : usize, road : &Road, lane : usize, behind : f32, ahead : f32, player : &TrafficPlayer) -> bool {
+        let car = &self.cars[car_id];
+        let lane_offset = match road.get_lane_offset(lane, car.road_distance) {
+            Some(lane_offset) => { lane_offset }
+            None => { return false; }
+        };
+
+        let sign = car.get_direction_sign();
+        let is_free = |road_distance : f32, x_pos : f32, width : f32| {
+            let distance = (road_distance - car.road_distance) * sign;
+            (x_pos - lane_offset).abs() > (width + car.width) * 0.5 || distance < -behind || distance > ahead
+        };
+
+        is_free(player.road_distance, player.x_pos, player.width) &&
+        self.cars.iter().enumerate().all(|(i, other)| i == car_id || is_free(other.road_distance, other.x_pos, other.width))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use image::{Rgb, RgbImage, RgbaImage};
+
+    use super::*;
+
+    const DELTA_TIME : f32 = 0.05;
+    // Two lanes, the oncoming one on the left, centered at -0.5 and 0.5.
+    const ONCOMING_LANE_X : f32 = -0.5;
+    const OWN_LANE_X : f32 = 0.5;
+    const CAR_WIDTH : f32 = 0.4;
+    const STEER_SPEED : f32 = 2.0;
+
+    fn make_track() -> Track {
+        let road = Road::new(2.0, vec![LaneDirection::Backward, LaneDirection::Forward], vec![KeyPoint::new(0.0, 0.0), KeyPoint::new(1000.0, 0.0)], Rc::new(RgbImage::new(1, 1)));
+        Track::new(TrackData::new(1000.0, Vec::new(), Vec::new(), vec![road], (Rgb([0, 0, 0]), Rgb([0, 0, 0]))))
+    }
+
+    // Camera sees the whole track, so no car is despawned.
+    fn make_camera(road_distance : f32) -> Camera {
+        Camera { screen_dist : 1.0, viewport_height : 1.0, y_pos : 1.0, far_plane : 1000.0, pitch : 0.0, road_distance, x_offset : 0.0 }
+    }
+
+    fn make_car(road_distance : f32, x_pos : f32, speed : f32, temper : f32) -> TrafficCar {
+        let billboard = BillboardFactory::from_image(&RgbaImage::new(8, 8)).construct(road_distance, x_pos);
+        TrafficCar::new(billboard, CAR_WIDTH, speed, STEER_SPEED, temper)
+    }
+
+    fn make_player(road_distance : f32, x_pos : f32, speed : f32, braking : bool) -> TrafficPlayer {
+        TrafficPlayer { road_distance, x_pos, width : CAR_WIDTH, speed, braking }
+    }
+
+    fn make_traffic(overtake_distance : f32, cars : Vec<TrafficCar>, billboards : &mut Billboards) -> Traffic {
+        let mut traffic = Traffic::new(overtake_distance);
+        for car in cars { traffic.add_car(billboards, car); }
+        traffic
+    }
+
+    // Player keeps going at its speed, events are returned with the time they were given at.
+    fn drive(traffic : &mut Traffic, player : &mut TrafficPlayer, billboards : &mut Billboards, time : f32) -> Vec<(f32, TrafficEvent)> {
+        let track = make_track();
+        let camera = make_camera(0.0);
+        let mut events = Vec::new();
+        for step in 0..(time / DELTA_TIME).round() as usize {
+            let step_events = traffic.update(&track, &camera, player, DELTA_TIME, billboards);
+            events.extend(step_events.into_iter().map(|event| (step as f32 * DELTA_TIME, event)));
+            player.road_distance += player.speed * DELTA_TIME;
         }
+        events
+    }
+
+    fn far_player() -> TrafficPlayer {
+        make_player(900.0, OWN_LANE_X, 0.0, false)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn follower_takes_leader_speed_and_keeps_safe_gap() {
+        let mut billboards = Billboards::new();
+        // No overtaking, so the follower has to stay behind.
+        let mut traffic = make_traffic(0.0, vec![make_car(5.0, OWN_LANE_X, 1.0, 0.0), make_car(0.0, OWN_LANE_X, 3.0, 0.0)], &mut billboards);
+        let mut player = far_player();
+
+        let track = make_track();
+        let camera = make_camera(0.0);
+        for _ in 0..(60.0 / DELTA_TIME) as usize {
+            traffic.update(&track, &camera, &player, DELTA_TIME, &mut billboards);
+            let gap = traffic.cars[0].road_distance - traffic.cars[1].road_distance;
+            assert!(gap > MIN_GAP, "gap {} is below the minimal one", gap);
+            player.road_distance += player.speed * DELTA_TIME;
+        }
+
+        let (leader, follower) = (&traffic.cars[0], &traffic.cars[1]);
+        let safe_gap = MIN_GAP + leader.speed * HEADWAY_TIME.0;
+        // Speed changes by steps of one frame's acceleration, so it only stays around the leader's speed.
+        assert!((follower.speed - leader.speed).abs() <= ACCELERATION * DELTA_TIME + 0.001, "follower speed {} differs from leader speed {}", follower.speed, leader.speed);
+        assert!((leader.road_distance - follower.road_distance - safe_gap).abs() < 0.05);
+        assert_eq!(follower.lane, Some(1));
+    }
+
+    #[test]
+    fn braking_player_makes_follower_brake() {
+        let track = make_track();
+        let camera = make_camera(0.0);
+        for &player_braking in &[false, true] {
+            let mut billboards = Billboards::new();
+            let mut traffic = make_traffic(0.0, vec![make_car(0.0, OWN_LANE_X, 2.0, 0.0)], &mut billboards);
+            // Far enough for a normal following distance, too close for the distance kept behind a braking car.
+            let player = make_player(4.5, OWN_LANE_X, 1.0, player_braking);
+            traffic.update(&track, &camera, &player, DELTA_TIME, &mut billboards);
+
+            assert_eq!(traffic.cars[0].braking, player_braking);
+            let expected_speed = if player_braking { 2.0 - BRAKE_DECELERATION * DELTA_TIME } else { 2.0 };
+            assert!((traffic.cars[0].speed - expected_speed).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn braking_leader_makes_follower_brake() {
+        let mut billboards = Billboards::new();
+        let mut traffic = make_traffic(0.0, vec![make_car(4.5, OWN_LANE_X, 1.0, 0.0), make_car(0.0, OWN_LANE_X, 2.0, 0.0)], &mut billboards);
+        // Stopped player right ahead of the leader.
+        let mut player = make_player(5.5, OWN_LANE_X, 0.0, false);
+        drive(&mut traffic, &mut player, &mut billboards, DELTA_TIME);
+
+        assert!(traffic.cars[0].braking);
+        assert!(traffic.cars[1].braking);
+    }
+
+    #[test]
+    fn follower_overtakes_on_free_oncoming_lane() {
+        let mut billboards = Billboards::new();
+        let overtake_distance = 4.0;
+        let mut traffic = make_traffic(overtake_distance, vec![make_car(8.0, OWN_LANE_X, 0.5, 0.0), make_car(0.0, OWN_LANE_X, 2.0, 0.0)], &mut billboards);
+        let mut player = far_player();
+
+        let track = make_track();
+        let camera = make_camera(0.0);
+        let mut overtake_gap = None;
+        let mut passed = false;
+        for _ in 0..(30.0 / DELTA_TIME) as usize {
+            traffic.update(&track, &camera, &player, DELTA_TIME, &mut billboards);
+            let gap = traffic.cars[0].road_distance - traffic.cars[1].road_distance;
+            if overtake_gap.is_none() && traffic.cars[1].lane == Some(0) { overtake_gap = Some(gap); }
+            if gap < 0.0 { passed = true; }
+            player.road_distance += player.speed * DELTA_TIME;
+        }
+
+        let overtake_gap = overtake_gap.expect("follower didn't go to the oncoming lane");
+        assert!(overtake_gap > 0.0 && overtake_gap < overtake_distance, "overtaking started {} ahead of the leader", overtake_gap);
+        assert!(passed);
+        // Overtaking car returns to its lane ahead of the overtaken one.
+        let (overtaken, overtaking) = (&traffic.cars[0], &traffic.cars[1]);
+        assert_eq!(overtaking.lane, Some(1));
+        assert!((overtaking.x_pos - OWN_LANE_X).abs() < 0.001);
+        assert!(overtaking.road_distance - overtaken.road_distance > MERGE_GAP);
+    }
+
+    #[test]
+    fn follower_doesnt_overtake_on_busy_oncoming_lane() {
+        let mut billboards = Billboards::new();
+        let cars = vec![
+            make_car(3.0, OWN_LANE_X, 0.5, 0.0), 
+            make_car(0.0, OWN_LANE_X, 2.0, 0.0),
+            make_car(30.0, ONCOMING_LANE_X, -1.0, 0.0)
+        ];
+        let mut traffic = make_traffic(4.0, cars, &mut billboards);
+        let mut player = far_player();
+
+        let track = make_track();
+        let camera = make_camera(0.0);
+        for _ in 0..(5.0 / DELTA_TIME) as usize {
+            traffic.update(&track, &camera, &player, DELTA_TIME, &mut billboards);
+            assert_eq!(traffic.cars[1].lane, Some(1));
+            assert!(traffic.cars[0].road_distance - traffic.cars[1].road_distance > MIN_GAP);
+            player.road_distance += player.speed * DELTA_TIME;
+        }
+    }
+
+    #[test]
+    fn car_stuck_behind_player_honks() {
+        let mut billboards = Billboards::new();
+        let mut traffic = make_traffic(0.0, vec![make_car(0.0, OWN_LANE_X, 2.0, 0.0)], &mut billboards);
+        let mut player = make_player(3.0, OWN_LANE_X, 0.0, false);
+        let events = drive(&mut traffic, &mut player, &mut billboards, 8.0);
+
+        assert_eq!(events.len(), 1);
+        let (time, event) = events[0];
+        assert!(event == TrafficEvent::Honk);
+        assert!(time > HONK_PATIENCE.0, "honked at {} before running out of patience", time);
+    }
+
+    #[test]
+    fn hasty_driver_honks_sooner() {
+        let mut billboards = Billboards::new();
+        let mut traffic = make_traffic(0.0, vec![make_car(0.0, OWN_LANE_X, 2.0, 1.0)], &mut billboards);
+        let mut player = make_player(3.0, OWN_LANE_X, 0.0, false);
+        let events = drive(&mut traffic, &mut player, &mut billboards, HONK_PATIENCE.0);
+
+        assert!(!events.is_empty() && events[0].1 == TrafficEvent::Honk);
+        assert!(events[0].0 < HONK_PATIENCE.0);
+    }
+
+    #[test]
+    fn car_stuck_behind_other_car_doesnt_honk() {
+        let mut billboards = Billboards::new();
+        let mut traffic = make_traffic(0.0, vec![make_car(3.0, OWN_LANE_X, 0.0, 0.0), make_car(0.0, OWN_LANE_X, 2.0, 1.0)], &mut billboards);
+        let mut player = far_player();
+
+        assert!(drive(&mut traffic, &mut player, &mut billboards, 10.0).is_empty());
+    }
+
+    #[test]
+    fn oncoming_car_flashes_headlights_at_player_in_its_lane() {
+        for &(player_x, expect_flash) in &[(ONCOMING_LANE_X, true), (OWN_LANE_X, false)] {
+            let mut billboards = Billboards::new();
+            let mut traffic = make_traffic(0.0, vec![make_car(20.0, ONCOMING_LANE_X, -1.0, 0.0)], &mut billboards);
+            let mut player = make_player(0.0, player_x, 1.0, false);
+            let events = drive(&mut traffic, &mut player, &mut billboards, 1.0);
+
+            let flashes = events.iter().filter(|(_, event)| *event == TrafficEvent::FlashHeadlights).count();
+            // Cooldown keeps the driver from flashing every frame.
+            assert_eq!(flashes, if expect_flash { 1 } else { 0 });
+            assert!(events.iter().all(|(_, event)| *event != TrafficEvent::Honk));
+        }
+    }
+
+    // Spawns a slow car in the own lane and an oncoming one in every cell.
+    struct TestSpawner {
+        factory : BillboardFactory
+    }
+
+    impl TrafficSpawner for TestSpawner {
+        fn spawn(&self, cell : usize, start : f32, end : f32) -> Vec<TrafficCar> {
+            let temper = (cell % 3) as f32 * 0.5;
+            let own_lane_car = TrafficCar::new(self.factory.construct(start, OWN_LANE_X), CAR_WIDTH, 0.5 + temper, STEER_SPEED, temper);
+            let oncoming_car = TrafficCar::new(self.factory.construct((start + end) * 0.5, ONCOMING_LANE_X), CAR_WIDTH, -1.0, STEER_SPEED, temper);
+            vec![own_lane_car, oncoming_car]
+        }
+    }
+
+    type CarState = (u32, f32, f32, f32, Option<usize>, bool);
+
+    fn record_drive() -> (Vec<Vec<CarState>>, Vec<(usize, TrafficEvent)>) {
+        let track = make_track();
+        let mut billboards = Billboards::new();
+        let mut traffic = Traffic::new(4.0);
+        traffic.set_spawner(Rc::new(TestSpawner { factory : BillboardFactory::from_image(&RgbaImage::new(8, 8)) }));
+        let mut player = make_player(0.0, OWN_LANE_X, 3.0, false);
+        let mut camera = make_camera(0.0);
+        camera.far_plane = 30.0;
+
+        let mut states = Vec::new();
+        let mut events = Vec::new();
+        for step in 0..(60.0 / DELTA_TIME) as usize {
+            let step_events = traffic.update(&track, &camera, &player, DELTA_TIME, &mut billboards);
+            events.extend(step_events.into_iter().map(|event| (step, event)));
+            states.push(traffic.cars.iter().map(|car| (car.billboard_id.0, car.road_distance, car.x_pos, car.speed, car.lane, car.braking)).collect());
+            player.road_distance += player.speed * DELTA_TIME;
+            camera.road_distance = player.road_distance;
+        }
+        (states, events)
+    }
+
+    #[test]
+    fn same_traffic_drives_the_same_way() {
+        let (states, events) = record_drive();
+        let (other_states, other_events) = record_drive();
+
+        assert!(states.iter().any(|cars| !cars.is_empty()));
+        assert!(states == other_states);
+        assert!(events == other_events);
+    }
+
+    #[test]
+    fn car_off_every_road_waits() {
+        let track = Track::new(TrackData::new(1000.0, Vec::new(), Vec::new(), Vec::new(), (Rgb([0, 0, 0]), Rgb([0, 0, 0]))));
+        let mut billboards = Billboards::new();
+        let mut traffic = make_traffic(0.0, vec![make_car(5.0, OWN_LANE_X, 1.0, 0.0)], &mut billboards);
+        traffic.update(&track, &make_camera(0.0), &far_player(), DELTA_TIME, &mut billboards);
+
+        assert_eq!(traffic.cars[0].road_distance, 5.0);
+    }
+
+    // Remembers the cells it has spawned.
+    struct CountingSpawner {
+        spawner : TestSpawner,
+        cells : RefCell<Vec<usize>>
+    }
+
+    impl TrafficSpawner for CountingSpawner {
+        fn spawn(&self, cell : usize, start : f32, end : f32) -> Vec<TrafficCar> {
+            self.cells.borrow_mut().push(cell);
+            self.spawner.spawn(cell, start, end)
+        }
+    }
+
+    #[test]
+    fn reversed_traffic_doesnt_spawn_cells_again() {
+        let track = make_track();
+        let spawner = Rc::new(CountingSpawner { spawner : TestSpawner { factory : BillboardFactory::from_image(&RgbaImage::new(8, 8)) }, cells : RefCell::new(Vec::new()) });
+        let mut billboards = Billboards::new();
+        let mut traffic = Traffic::new(4.0);
+        traffic.set_spawner(spawner.clone());
+        let mut camera = make_camera(500.0);
+        camera.far_plane = 30.0;
+        traffic.update(&track, &camera, &far_player(), DELTA_TIME, &mut billboards);
+
+        // Player turns back in the middle of the road and drives to its start.
+        let reversed_track = Track::new(track.data.reversed());
+        let mut billboards = billboards.reversed(1000.0);
+        let mut traffic = traffic.reversed(1000.0);
+        while camera.road_distance < 1000.0 {
+            traffic.update(&reversed_track, &camera, &far_player(), DELTA_TIME, &mut billboards);
+            camera.road_distance += 10.0 * DELTA_TIME;
+        }
+
+        let mut cells = spawner.cells.borrow().clone();
+        let spawned_count = cells.len();
+        cells.sort();
+        cells.dedup();
+        assert!(spawned_count > 0);
+        assert_eq!(cells.len(), spawned_count);
+    }
+}
@@ -44,4 +44,31 @@ impl Font {
         }
         symbol_img.unwrap()
     }
+
+    pub fn get_width(&self, text : &str) -> u32 {
+        text.chars().map(|symbol| self.get_symbol(symbol).width()).sum()
+    }
+
+    // Words go to the next line when the line gets wider than max_width.
+    pub fn wrap(&self, text : &str, max_width : u32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for word in text.split_whitespace() {
+            let extended_line = if line.is_empty() { String::from(word) } else { format!("{} {}", line, word) };
+            if !line.is_empty() && self.get_width(&extended_line) > max_width {
+                lines.push(line);
+                line = String::from(word);
+            } else {
+                line = extended_line;
+            }
+        }
+        if !line.is_empty() { lines.push(line); }
+
+        lines
+    }
+
+    // Letters are capitalized, other symbols missing in the font are skipped.
+    pub fn to_printable(&self, text : &str) -> String {
+        text.to_uppercase().chars().filter(|symbol| self.symbols.contains_key(symbol)).collect()
+    }
 }
\ No newline at end of file
@@ -6,11 +6,15 @@ mod ui_text;
 mod ui_image;
 mod ui_selector;
 mod ui_void;
+mod ui_bar;
+mod ui_polyline;
 
 pub use ui_text::*;
 pub use ui_image::*;
 pub use ui_selector::*;
 pub use ui_void::*;
+pub use ui_bar::*;
+pub use ui_polyline::*;
 
 pub trait UIControl {
     fn draw(&self, buffer : &mut RgbImage);
@@ -0,0 +1,38 @@
+use image::{RgbImage, Rgb};
+
+use crate::engine::common::{IVec2, Math};
+use super::UIControl;
+
+// Horizontal bar filled from the left, fill is from 0 to 1.
+pub struct UIBar {
+    size : IVec2,
+    fill : f32,
+    color : Rgb<u8>,
+    background_color : Rgb<u8>,
+    position : IVec2
+}
+
+impl UIBar {
+    pub fn new(size : IVec2, fill : f32, color : Rgb<u8>, background_color : Rgb<u8>) -> UIBar {
+        UIBar { size, fill : Math::min(Math::max(fill, 0.0), 1.0), color, background_color, position : IVec2::zero() }
+    }
+}
+
+impl UIControl for UIBar {
+    fn draw(&self, buffer : &mut RgbImage) {
+        let fill_width = (self.size.x as f32 * self.fill).round() as isize;
+
+        for x in Math::max(0, -self.position.x)..Math::min(self.size.x, buffer.width() as isize - self.position.x) {
+            let color = if x < fill_width { self.color } else { self.background_color };
+            for y in Math::max(0, -self.position.y)..Math::min(self.size.y, buffer.height() as isize - self.position.y) {
+                buffer.put_pixel((self.position.x + x) as u32, (self.position.y + y) as u32, color);
+            }
+        }
+    }
+
+    fn get_size(&self) -> IVec2 { self.size }
+
+    fn set_position(&mut self, position : IVec2) { self.position = position; }
+
+    fn get_position(&self) -> IVec2 { self.position }
+}
@@ -0,0 +1,50 @@
+use image::{RgbImage, Rgb};
+
+use crate::engine::common::{IVec2, Vec2, ImageOps};
+use super::UIControl;
+
+// Line through the points given in fractions of the control size.
+pub struct UIPolyline {
+    size : IVec2,
+    points : Vec<Vec2>,
+    color : Rgb<u8>,
+    background_color : Option<Rgb<u8>>,
+    position : IVec2
+}
+
+impl UIPolyline {
+    pub fn new(size : IVec2, points : Vec<Vec2>, color : Rgb<u8>, background_color : Option<Rgb<u8>>) -> UIPolyline {
+        UIPolyline { size, points, color, background_color, position : IVec2::zero() }
+    }
+
+    fn to_pixel(&self, point : &Vec2) -> IVec2 {
+        IVec2::new(
+            self.position.x + (point.x * (self.size.x - 1) as f32).round() as isize,
+            self.position.y + (point.y * (self.size.y - 1) as f32).round() as isize
+        )
+    }
+}
+
+impl UIControl for UIPolyline {
+    fn draw(&self, buffer : &mut RgbImage) {
+        if let Some(background_color) = self.background_color {
+            for x in 0..self.size.x {
+                for y in 0..self.size.y {
+                    let pixel = IVec2::new(self.position.x + x, self.position.y + y);
+                    if pixel.x < 0 || pixel.y < 0 || pixel.x >= buffer.width() as isize || pixel.y >= buffer.height() as isize { continue; }
+                    buffer.put_pixel(pixel.x as u32, pixel.y as u32, background_color);
+                }
+            }
+        }
+
+        for segment in self.points.windows(2) {
+            ImageOps::draw_line_one_pixel(buffer, &self.to_pixel(&segment[0]), &self.to_pixel(&segment[1]), &self.color);
+        }
+    }
+
+    fn get_size(&self) -> IVec2 { self.size }
+
+    fn set_position(&mut self, position : IVec2) { self.position = position; }
+
+    fn get_position(&self) -> IVec2 { self.position }
+}
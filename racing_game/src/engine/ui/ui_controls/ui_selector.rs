@@ -87,6 +87,15 @@ impl<E : Clone> UISelector<E> {
     pub fn select_current(&mut self) -> E {
         self.control_events[self.selected_item].as_ref().clone()
     }
+
+    pub fn get_selected_item(&self) -> usize {
+        self.selected_item
+    }
+
+    // Keeps the selection when the menu is rebuilt with the same items.
+    pub fn set_selected_item(&mut self, item : usize) {
+        if item < self.control_events.len() { self.selected_item = item; }
+    }
 }
 
 impl<E> UIControl for UISelector<E> where E : Clone {
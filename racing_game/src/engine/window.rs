@@ -71,4 +71,20 @@ impl Window{
     pub fn set_time(&mut self, time : f64) {
         self.glfw.set_time(time);
     }
-}
\ No newline at end of file
+}
+
+// GLFW key codes, see glfw3.h.
+const KEY_CODE_RANGES : [(i32, i32); 14] = [
+    (-1, -1), (32, 32), (39, 39), (44, 57), (59, 59), (61, 61), (65, 93), 
+    (96, 96), (161, 162), (256, 269), (280, 284), (290, 314), (320, 336), (340, 348)
+];
+
+pub fn key_from_code(code : i32) -> Option<Key> {
+    for &(first, last) in &KEY_CODE_RANGES {
+        if code >= first && code <= last {
+            return Some(unsafe { std::mem::transmute::<i32, Key>(code) });
+        }
+    }
+
+    None
+}
@@ -10,11 +10,13 @@ pub enum CityDescription {
 pub struct City {
     pub position : IVec2,
     pub description : CityDescription,
-    pub services : CityServicesSubset
+    pub services : CityServicesSubset,
+    // Multiplier of all the prices in the city.
+    pub price_level : f32
 }
 
 impl City {
-    pub fn new(position : IVec2, description : CityDescription, services : CityServicesSubset) -> City {
-        City { position, description, services }
+    pub fn new(position : IVec2, description : CityDescription, services : CityServicesSubset, price_level : f32) -> City {
+        City { position, description, services, price_level }
     }
 }
\ No newline at end of file
@@ -1,27 +1,43 @@
+use std::rc::Rc;
+
 use rand::{RngCore, rngs::StdRng};
 
-use crate::engine::billboards::*;
-use crate::engine::common::{IVec2, LineSegment, Geometry};
+use crate::engine::common::{IVec2, Vec2, LineSegment, Geometry};
 use crate::game::player::Player;
-use crate::game::Game;
+use crate::game::Percent;
 use crate::game::ride::car::Car;
+use crate::game::save::*;
 
 pub mod city;
 pub mod road_path;
+pub mod scenery;
+pub mod road_traffic;
+mod sketch;
 
 use city::*;
 use road_path::*;
+use scenery::*;
+use road_traffic::*;
 
 pub mod services;
 use services::*;
 
+#[derive(Clone)]
 pub struct GenerationParameters{
     pub city_count : u32,
     pub size : IVec2,
     pub min_distance_between_cities : f32,
-    pub road_length_multiplier : f32
+    pub road_length_multiplier : f32,
+    // Brands of every service type, cities get their services from them.
+    pub service_count : u32
 }
 
+// Prices are higher in remote cities and in cities with few roads.
+const MIN_PRICE_LEVEL : f32 = 0.8;
+const REMOTENESS_PRICE_LEVEL : f32 = 0.4;
+const MISSING_ROAD_PRICE_LEVEL : f32 = 0.1;
+const WELL_CONNECTED_ROAD_COUNT : usize = 3;
+
 #[readonly::make]
 pub struct CityMap{
     pub cities : Vec<City>,
@@ -30,7 +46,8 @@ pub struct CityMap{
     pub size : IVec2,
     pub current_city_id : usize,
     current_destination_city_id : usize,
-    billboard_factories : Vec<BillboardFactory>
+    pub parameters : GenerationParameters,
+    scenery : Scenery
 }
 
 impl CityMap {
@@ -226,14 +243,16 @@ impl CityMap {
         }     
     }
 
-    fn create_billboard_factories() -> Vec<BillboardFactory> {
-        let mut factories : Vec<BillboardFactory> = Vec::new();
+    fn generate_price_level(city_id : usize, city_positions : &[IVec2], roads : &[(usize, usize)], size : &IVec2) -> f32 {
+        let center = Vec2::new(size.x as f32 * 0.5, size.y as f32 * 0.5);
+        let position = Vec2::new(city_positions[city_id].x as f32, city_positions[city_id].y as f32);
+        let remoteness = (&position - &center).len() / center.len();
 
-        let spritesheet = Game::load_image_rgba("test_spritesheet.png");
-        let spritesheet_meta = Game::load_file("test_spritesheet.meta");
-        factories.push(BillboardFactory::new(&spritesheet, spritesheet_meta));
+        let road_count = roads.iter().filter(|road| road.0 == city_id || road.1 == city_id).count();
+        let missing_roads = WELL_CONNECTED_ROAD_COUNT.saturating_sub(road_count);
 
-        factories
+        let price_level = MIN_PRICE_LEVEL + REMOTENESS_PRICE_LEVEL * remoteness + MISSING_ROAD_PRICE_LEVEL * missing_roads as f32;
+        (price_level * 100.0).round() / 100.0
     }
 
     pub fn generate(rng : &mut StdRng, parameters : GenerationParameters) -> CityMap {
@@ -242,9 +261,17 @@ impl CityMap {
         Self::remove_some_roads(&mut roads, &city_positions, rng);
         let (start_city_id, finish_city_id) = Self::select_ending_cities(&city_positions, &roads);
 
-        let billboard_factories = Self::create_billboard_factories();
+        let scenery = Scenery::generate(rng);
+        let biome_map = BiomeMap::generate(rng, &parameters.size);
+        let vehicles = Rc::from(Vehicles::generate(rng));
+        let get_city_size = |city_id : usize| roads.iter().filter(|road| road.0 == city_id || road.1 == city_id).count();
+        let city_sizes : Vec<usize> = (0..city_positions.len()).map(get_city_size).collect();
+
+        let price_levels : Vec<f32> = (0..city_positions.len())
+        .map(|id| Self::generate_price_level(id, &city_positions, &roads, &parameters.size))
+        .collect();
 
-        let services = Services::generate(rng);
+        let services = Services::generate(rng, city_positions.len(), parameters.service_count as usize);
         let mut service_subsets = Vec::new();
         for _i in 0..city_positions.len() {
             service_subsets.push(services.generate_subset(
@@ -252,7 +279,8 @@ impl CityMap {
                     gas_station_count : 3,  
                     hostel_count : 3,
                     repair_station_count : 3,
-                    shop_count : 3
+                    shop_count : 3,
+                    job_board_count : 2
                 }, 
             rng));
         }
@@ -262,14 +290,21 @@ impl CityMap {
         .collect();
         
         for road in &mut roads{ 
-            let road_len = (&city_positions[road.source_id] - &city_positions[road.destination_id]).len();
-            road.generate(rng, &billboard_factories, road_len * parameters.road_length_multiplier);
+            let (source, destination) = (&city_positions[road.source_id], &city_positions[road.destination_id]);
+            let road_len = (source - destination).len();
+            let middle = Vec2::new((source.x + destination.x) as f32 * 0.5, (source.y + destination.y) as f32 * 0.5);
+            let traffic_properties = TrafficProperties {
+                vehicles : vehicles.clone(),
+                source_city_size : city_sizes[road.source_id],
+                destination_city_size : city_sizes[road.destination_id]
+            };
+            road.generate(rng, &scenery, biome_map.get_biome(&middle), traffic_properties, road_len * parameters.road_length_multiplier);
         }
 
         let cities : Vec<City> = city_positions.into_iter()
         .enumerate()
-        .zip(service_subsets.into_iter())
-        .map(|((id, pos), services)| City::new( 
+        .zip(service_subsets.into_iter().zip(price_levels.into_iter()))
+        .map(|((id, pos), (services, price_level))| City::new( 
             pos,  
             if id == start_city_id { 
                 CityDescription::Start 
@@ -278,11 +313,21 @@ impl CityMap {
             } else { 
                 CityDescription::Intermediate 
             },
-            services
+            services,
+            price_level
         ))
         .collect();
 
-        CityMap { cities, roads, services, size : parameters.size, current_city_id : start_city_id, current_destination_city_id : start_city_id, billboard_factories }
+        CityMap { 
+            cities, 
+            roads, 
+            services, 
+            size : parameters.size, 
+            current_city_id : start_city_id, 
+            current_destination_city_id : start_city_id, 
+            parameters, 
+            scenery 
+        }
     }
 }
 
@@ -295,6 +340,14 @@ impl CityMap {
         self.current_destination_city_id = destination;
     }
 
+    pub fn is_in_start_city(&self) -> bool {
+        matches!(self.cities[self.current_city_id].description, CityDescription::Start)
+    }
+
+    pub fn is_in_finish_city(&self) -> bool {
+        matches!(self.cities[self.current_city_id].description, CityDescription::Finish)
+    }
+
     pub fn get_accesible_city_ids(&self) -> Vec<usize>{
         let mut accesible : Vec<usize> = Vec::new();
         for road in &self.roads {
@@ -323,18 +376,39 @@ impl CityMap {
         accesible
     }
 
-    pub fn get_current_road_meta(&self) -> RoadPathMeta {
+    pub fn get_current_road_meta(&self) -> Result<RoadPathMeta, String> {
         for road in &self.roads {
-            if road.source_id == self.current_city_id || road.destination_id == self.current_destination_city_id {
-                return road.get_meta();
+            if road.source_id == self.current_city_id && road.destination_id == self.current_destination_city_id {
+                return Ok(road.get_meta());
             }
 
             if road.source_id == self.current_destination_city_id && road.destination_id == self.current_city_id {
-                return road.get_reverse_meta();
+                return Ok(road.get_reverse_meta());
             }
         }
 
-        panic!("incorrect road!");
+        Err(format!("there is no road from city {} to city {}", self.current_city_id, self.current_destination_city_id))
+    }
+
+    pub fn get_distance_to(&self, city_id : usize) -> f32 {
+        (&self.cities[self.current_city_id].position - &self.cities[city_id].position).len()
+    }
+
+    // Terms of the contract if it is taken in the current city now.
+    pub fn get_contract_offer(&self, job_board_id : ServiceId, contract_id : usize, now : f32) -> Cargo {
+        let contract = &self.get_service::<JobBoard>(job_board_id).contracts[contract_id];
+        let distance = self.get_distance_to(contract.destination_city_id);
+        Cargo {
+            destination_city_id : contract.destination_city_id,
+            reward : contract.get_reward(distance),
+            deadline : now + contract.get_minutes(distance),
+            fragility : contract.fragility,
+            condition : Percent(100.0)
+        }
+    }
+
+    pub fn get_price_level(&self) -> f32 {
+        self.cities[self.current_city_id].price_level
     }
 
     pub fn get_current_city_services_subset(&self) -> &CityServicesSubset {
@@ -345,7 +419,87 @@ impl CityMap {
         self.services.get_service::<T>(id)
     }
 
-    pub fn process_service_action(&mut self, service_id : ServiceId, action : ServiceAction, player : &mut Player, car : &mut Car) { 
-        self.services.process_action(service_id, action, player, car);
+    pub fn save(&self, save : &mut SaveWriter) {
+        save.write("current_city", vec![self.current_city_id.to_string()]);
+        save.write("destination_city", vec![self.current_destination_city_id.to_string()]);
+        self.services.save(save);
     }
-}
\ No newline at end of file
+
+    // Applies saved progress to the map generated with the same seed and parameters.
+    pub fn load(&mut self, save : &SaveReader) -> Result<(), String> {
+        let current_city_id = save.get("current_city")?.get::<usize>(0)?;
+        let current_destination_city_id = save.get("destination_city")?.get::<usize>(0)?;
+        if current_city_id >= self.cities.len() || current_destination_city_id >= self.cities.len() {
+            return Err(String::from("incorrect city id"));
+        }
+
+        self.current_city_id = current_city_id;
+        self.current_destination_city_id = current_destination_city_id;
+        self.services.load(save)
+    }
+
+    pub fn process_service_action(&mut self, service_id : ServiceId, action : ServiceAction, player : &mut Player, car : &mut Car) -> Result<(), String> { 
+        let price_level = self.get_price_level();
+        self.services.process_action(service_id, action, player, car, price_level)
+    }
+
+    pub fn pass_time(&mut self, minutes : f32) {
+        self.services.pass_time(minutes);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use crate::game::new_game::{NewGameParameters, WorldSeed};
+
+    use super::*;
+
+    // Minimal save version and the fingerprint of the map it was made with.
+    const GENERATION_FINGERPRINT : (u32, u64) = (12, 0x4bb5a812df510b96);
+
+    fn fnv1a(text : &str) -> u64 {
+        text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+    }
+
+    fn describe_services<T>(city_map : &CityMap, city : &City) -> String where T : Sized + 'static + Service {
+        city.services.get_of_type::<T>().into_iter()
+        .map(|id| format!("{}:{} ", id.0, city_map.get_service::<T>(id).get_name()))
+        .collect()
+    }
+
+    // Everything save files refer to : cities, roads and services of every city.
+    fn describe(city_map : &CityMap) -> String {
+        let mut description = String::new();
+        for city in &city_map.cities {
+            let kind = match city.description {
+                CityDescription::Start => { "start" }
+                CityDescription::Finish => { "finish" }
+                CityDescription::Intermediate => { "intermediate" }
+            };
+            description += &format!("city {} {} {} {}\n", city.position.x, city.position.y, kind, city.price_level);
+            description += &describe_services::<GasStation>(city_map, city);
+            description += &describe_services::<Hostel>(city_map, city);
+            description += &describe_services::<RepairStation>(city_map, city);
+            description += &describe_services::<Shop>(city_map, city);
+            description += &describe_services::<JobBoard>(city_map, city);
+            description += "\n";
+        }
+        for road in &city_map.roads {
+            description += &format!("road {} {} {:?}\n", road.source_id, road.destination_id, road.get_meta().length);
+        }
+
+        description
+    }
+
+    #[test]
+    fn map_generation_matches_save_version() {
+        let parameters = NewGameParameters::new(WorldSeed::from_code("0").unwrap());
+        let city_map = CityMap::generate(&mut parameters.seed.rng(), parameters.generation);
+
+        let fingerprint = (MIN_SAVE_VERSION, fnv1a(&describe(&city_map)));
+        assert!(
+            fingerprint == GENERATION_FINGERPRINT, 
+            "map generation changed, older saves point to other cities and services : raise SAVE_VERSION and MIN_SAVE_VERSION, then set GENERATION_FINGERPRINT to them and {:#x}", 
+            fingerprint.1
+        );
+    }
+}
@@ -7,6 +7,11 @@ use crate::engine::traffic::*;
 use crate::engine::track::*;
 use crate::game::Game;
 
+use super::scenery::*;
+use super::road_traffic::*;
+
+const LANE_WIDTH : f32 = 0.5;
+
 #[readonly::make]
 pub struct RoadPath {
     pub source_id : usize,
@@ -28,7 +33,8 @@ impl RoadPath {
         RoadPath { source_id, destination_id, meta : None }
     }
 
-    pub fn generate(&mut self, rng : &mut StdRng, billboard_factories : &Vec<BillboardFactory>, length : f32) {
+    // Traffic is made of the shared vehicles, its density depends on the sizes of the cities at the ends.
+    pub fn generate(&mut self, rng : &mut StdRng, scenery : &Scenery, biome : Biome, traffic_properties : TrafficProperties, length : f32) {
         let mut curvatures : Vec<Curvature>= Vec::new();
 
         let start_straight_len = rng.gen_range(50.0, 100.0);
@@ -48,31 +54,36 @@ impl RoadPath {
             if curr_dist + end_straight_len > length { break; }
         }
 
-        let heels = vec![
-            //Heel::new(0.0, 50.0, 0.0, 0.003),
-            //Heel::new(50.0, 100.0, 0.003, 0.0),
-            //Heel::new(75.0, 100.0, -0.001, 0.0)
-        ];
+        let mut heels : Vec<Heel> = Vec::new();
+        let mut curr_dist = rng.gen_range(10.0, 30.0);
+        loop {
+            let heel_len = rng.gen_range(20.0, 60.0);
+            if curr_dist + heel_len + end_straight_len > length { break; }
+
+            // Height is proportional to the length to keep slopes drivable.
+            let heel_height = if rng.gen_bool(0.6) { heel_len * rng.gen_range(0.02, 0.06) } else { -heel_len * rng.gen_range(0.02, 0.05) };
+            heels.push(Heel::new(curr_dist, curr_dist + heel_len, heel_height));
+
+            curr_dist += heel_len + rng.gen_range(0.0, 40.0);
+        }
+        let lanes = match rng.gen_range(0, 4) {
+            0 => { vec![LaneDirection::Backward, LaneDirection::Forward, LaneDirection::Forward] }
+            1 => { vec![LaneDirection::Backward, LaneDirection::Backward, LaneDirection::Forward, LaneDirection::Forward] }
+            _ => { vec![LaneDirection::Backward, LaneDirection::Forward] }
+        };
+        let road_width = lanes.len() as f32 * LANE_WIDTH;
         let mut roads = Vec::new();
         roads.push(Road::new(
-            1.0, 
+            road_width, 
+            lanes,
             vec![KeyPoint::new(0.0, 0.0), KeyPoint::new(10.0, 0.0), KeyPoint::new(30.0, 2.0), KeyPoint::new(50.0, 0.0), KeyPoint::new(length, 0.0)], 
             Rc::from(Game::load_image_rgb("road_tex.png"))
         ));
-        let track_data = TrackData::new(length, curvatures, heels, roads);
-        
-        let mut billboards = Billboards::new();
-        billboards.add_static(billboard_factories[0].construct(40.0, 1.1));
-        billboards.add_static(billboard_factories[0].construct(60.0, -1.1));
-        billboards.add_static(billboard_factories[0].construct(80.0, 1.1));
-        billboards.add_static(billboard_factories[0].construct(83.0, 1.1));
-        billboards.add_static(billboard_factories[0].construct(86.0, 1.1));
-        billboards.add_static(billboard_factories[0].construct(89.0, 1.1));
-
-        let mut traffic = Traffic::new(1.0);
-        let traffic_car_billboard = BillboardFactory::new(&Game::load_image_rgba("test_spritesheet.png"), Game::load_file("test_spritesheet.meta"));
-        let car = TrafficCar::new(traffic_car_billboard.construct(10.0, 0.0), 0.5, 1.0, 1.0);
-        traffic.add_car(&mut billboards, car);
+        let track_data = TrackData::new(length, curvatures, heels, roads, biome.get_ground_colors());
+        let billboards = scenery.place(biome, &track_data, length, rng);
+
+        let mut traffic = Traffic::new(4.0);
+        traffic.set_spawner(Rc::new(RoadTraffic::new(traffic_properties, rng.gen(), length)));
 
         let meta = RoadPathMeta { track_data : track_data, length, billboards, traffic };
         self.meta = Some(meta);
@@ -82,7 +93,14 @@ impl RoadPath {
         self.meta.clone().unwrap()
     }
 
+    // Road driven from the destination to the source.
     pub fn get_reverse_meta(&self) -> RoadPathMeta{
-        self.meta.clone().unwrap()
+        let meta = self.meta.as_ref().unwrap();
+        RoadPathMeta {
+            length : meta.length,
+            track_data : meta.track_data.reversed(),
+            billboards : meta.billboards.reversed(meta.length),
+            traffic : meta.traffic.reversed(meta.length)
+        }
     }
 }
\ No newline at end of file
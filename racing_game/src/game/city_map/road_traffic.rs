@@ -0,0 +1,219 @@
+use std::rc::Rc;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::engine::billboards::*;
+use crate::engine::common::Math;
+use crate::engine::traffic::*;
+use crate::game::Game;
+
+use super::sketch::Sketch;
+
+const SPRITE_VARIANT_COUNT : usize = 3;
+const SPRITE_COLOR_JITTER : i32 = 40;
+
+// Cars per road unit far from the cities.
+const OPEN_ROAD_DENSITY : f32 = 0.015;
+// Cities with more roads are bigger, so each of their roads adds traffic around them.
+const CITY_DENSITY_PER_ROAD : f32 = 0.01;
+const CITY_TRAFFIC_RANGE : f32 = 60.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum VehicleKind {
+    Car,
+    Truck,
+    Bus
+}
+
+const VEHICLE_KINDS : [(VehicleKind, f32); 3] = [(VehicleKind::Car, 6.0), (VehicleKind::Truck, 2.0), (VehicleKind::Bus, 1.0)];
+
+impl VehicleKind {
+    fn get_file_name(&self) -> &'static str {
+        match self {
+            VehicleKind::Car => { "car" }
+            VehicleKind::Truck => { "truck" }
+            VehicleKind::Bus => { "bus" }
+        }
+    }
+
+    // Width and height in road units.
+    fn get_size(&self) -> (f32, f32) {
+        match self {
+            VehicleKind::Car => { (0.4, 0.225) }
+            VehicleKind::Truck => { (0.5, 0.6) }
+            VehicleKind::Bus => { (0.5, 0.55) }
+        }
+    }
+
+    fn get_speed_range(&self) -> (f32, f32) {
+        match self {
+            VehicleKind::Car => { (2.0, 4.0) }
+            VehicleKind::Truck => { (1.5, 2.5) }
+            VehicleKind::Bus => { (1.5, 3.0) }
+        }
+    }
+
+    fn get_steer_speed(&self) -> f32 {
+        match self {
+            VehicleKind::Car => { 1.0 }
+            VehicleKind::Truck => { 0.5 }
+            VehicleKind::Bus => { 0.6 }
+        }
+    }
+
+    // Rear view, as every car is seen from behind or from the front in the distance.
+    fn generate_sprite(&self, rng : &mut StdRng) -> BillboardFactory {
+        let (width, height) = self.get_size();
+        let mut sketch = Sketch::new(width, height);
+        let paint = rng.gen_range(0, 3);
+        let mut color = |r : i32, g : i32, b : i32| Sketch::jitter_color(r, g, b, SPRITE_COLOR_JITTER, rng);
+
+        match self {
+            VehicleKind::Car => {
+                let body = [color(200, 40, 40), color(40, 80, 160), color(200, 200, 200)][paint];
+                sketch.fill_rect(0.15, 0.05, 0.85, 0.45, body);
+                sketch.fill_rect(0.0, 0.4, 1.0, 0.78, body);
+                sketch.fill_rect(0.22, 0.12, 0.78, 0.4, color(60, 90, 120));
+                sketch.fill_rect(0.4, 0.55, 0.6, 0.7, color(230, 230, 230));
+            }
+            VehicleKind::Truck => {
+                let cargo_box = color(190, 190, 180);
+                sketch.fill_rect(0.02, 0.0, 0.98, 0.78, cargo_box);
+                sketch.fill_rect(0.49, 0.05, 0.51, 0.75, color(120, 120, 110));
+            }
+            VehicleKind::Bus => {
+                let body = [color(220, 180, 40), color(40, 90, 170), color(180, 40, 40)];
+                sketch.fill_rect(0.0, 0.0, 1.0, 0.85, body[paint]);
+                sketch.fill_rect(0.1, 0.08, 0.9, 0.4, color(60, 90, 120));
+                sketch.fill_rect(0.0, 0.5, 1.0, 0.56, color(230, 230, 230));
+            }
+        }
+
+        let rear_lights = color(200, 30, 30);
+        sketch.fill_rect(0.05, 0.68, 0.18, 0.76, rear_lights);
+        sketch.fill_rect(0.82, 0.68, 0.95, 0.76, rear_lights);
+        sketch.fill_rect(0.0, 0.78, 1.0, 0.86, color(50, 50, 50));
+        let wheels = color(20, 20, 20);
+        sketch.fill_rect(0.08, 0.86, 0.3, 1.0, wheels);
+        sketch.fill_rect(0.7, 0.86, 0.92, 1.0, wheels);
+
+        sketch.finish()
+    }
+}
+
+// Sprites of every vehicle kind, shared by all the roads.
+pub struct Vehicles {
+    factories : Vec<(VehicleKind, Vec<BillboardFactory>)>
+}
+
+impl Vehicles {
+    // Sprites are loaded from traffic/<kind>.png when there is such file, generated otherwise.
+    pub fn generate(rng : &mut StdRng) -> Vehicles {
+        let factories = VEHICLE_KINDS.iter()
+        .map(|(kind, _)| {
+            let variants = match Game::find_image_rgba(&format!("traffic/{}.png", kind.get_file_name())) {
+                Some(sprite) => { vec![BillboardFactory::from_image(&sprite)] }
+                None => { (0..SPRITE_VARIANT_COUNT).map(|_| kind.generate_sprite(rng)).collect() }
+            };
+            (*kind, variants)
+        })
+        .collect();
+
+        Vehicles { factories }
+    }
+
+    fn get_variants(&self, kind : VehicleKind) -> &[BillboardFactory] {
+        &self.factories.iter().find(|(factory_kind, _)| *factory_kind == kind).unwrap().1
+    }
+}
+
+// What the traffic of one road depends on besides its length.
+pub struct TrafficProperties {
+    pub vehicles : Rc<Vehicles>,
+    // Road counts of the cities at the road ends.
+    pub source_city_size : usize,
+    pub destination_city_size : usize
+}
+
+// Traffic of one road, it is denser near the cities at its ends.
+pub struct RoadTraffic {
+    properties : TrafficProperties,
+    seed : u64,
+    length : f32
+}
+
+impl RoadTraffic {
+    pub fn new(properties : TrafficProperties, seed : u64, length : f32) -> RoadTraffic {
+        RoadTraffic { properties, seed, length }
+    }
+
+    fn get_density(&self, distance : f32) -> f32 {
+        let near_city = |distance_to_city : f32, city_size : usize| {
+            city_size as f32 * CITY_DENSITY_PER_ROAD * Math::max(1.0 - distance_to_city / CITY_TRAFFIC_RANGE, 0.0)
+        };
+        OPEN_ROAD_DENSITY + near_city(distance, self.properties.source_city_size) + near_city(self.length - distance, self.properties.destination_city_size)
+    }
+}
+
+impl TrafficSpawner for RoadTraffic {
+    // Every cell has its own random generator, so it is filled the same way whenever it is spawned.
+    fn spawn(&self, cell : usize, start : f32, end : f32) -> Vec<TrafficCar> {
+        let mut rng = StdRng::seed_from_u64(self.seed ^ (cell as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+        let expected_count = self.get_density((start + end) * 0.5) * (end - start);
+        let count = expected_count.floor() as usize + if rng.gen_bool(expected_count.fract() as f64) { 1 } else { 0 };
+        let total_weight = VEHICLE_KINDS.iter().fold(0.0, |sum, (_, weight)| sum + weight);
+
+        (0..count).map(|_| {
+            let mut choice = rng.gen_range(0.0, total_weight);
+            let kind = VEHICLE_KINDS.iter().find(|(_, weight)| { choice -= weight; choice < 0.0 }).unwrap_or(&VEHICLE_KINDS[0]).0;
+            let variants = self.properties.vehicles.get_variants(kind);
+            let billboard = variants[rng.gen_range(0, variants.len())].construct(rng.gen_range(start, end), 0.0);
+
+            // Half of the cars are oncoming.
+            let (min_speed, max_speed) = kind.get_speed_range();
+            let direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+            TrafficCar::new(billboard, kind.get_size().0, direction * rng.gen_range(min_speed, max_speed), kind.get_steer_speed(), rng.gen())
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROAD_LENGTH : f32 = 1000.0;
+    const CELL_LENGTH : f32 = 50.0;
+
+    fn spawn_road(vehicles : &Rc<Vehicles>, seed : u64) -> Vec<Vec<TrafficCar>> {
+        let properties = TrafficProperties { vehicles : vehicles.clone(), source_city_size : 3, destination_city_size : 1 };
+        let spawner = RoadTraffic::new(properties, seed, ROAD_LENGTH);
+        (0..(ROAD_LENGTH / CELL_LENGTH) as usize)
+        .map(|cell| spawner.spawn(cell, cell as f32 * CELL_LENGTH, (cell + 1) as f32 * CELL_LENGTH))
+        .collect()
+    }
+
+    #[test]
+    fn same_seed_spawns_the_same_traffic() {
+        let vehicles = Rc::new(Vehicles::generate(&mut StdRng::seed_from_u64(0)));
+        let cells = spawn_road(&vehicles, 42);
+
+        assert!(cells.iter().any(|cars| !cars.is_empty()));
+        assert!(cells == spawn_road(&vehicles, 42));
+        assert!(cells != spawn_road(&vehicles, 43));
+    }
+
+    // Cells are seeded on their own, so the order they are spawned in doesn't matter.
+    #[test]
+    fn cell_traffic_does_not_depend_on_spawn_order() {
+        let vehicles = Rc::new(Vehicles::generate(&mut StdRng::seed_from_u64(0)));
+        let cells = spawn_road(&vehicles, 42);
+
+        let properties = TrafficProperties { vehicles : vehicles.clone(), source_city_size : 3, destination_city_size : 1 };
+        let spawner = RoadTraffic::new(properties, 42, ROAD_LENGTH);
+        for cell in (0..cells.len()).rev() {
+            assert!(spawner.spawn(cell, cell as f32 * CELL_LENGTH, (cell + 1) as f32 * CELL_LENGTH) == cells[cell]);
+        }
+    }
+}
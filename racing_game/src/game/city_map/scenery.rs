@@ -0,0 +1,304 @@
+use std::f32::consts::PI;
+
+use rand::{Rng, rngs::StdRng};
+use image::Rgb;
+
+use crate::engine::billboards::*;
+use crate::engine::common::{IVec2, Vec2, Math};
+use crate::engine::track::TrackData;
+use crate::game::Game;
+
+use super::sketch::Sketch;
+
+const SPRITE_VARIANT_COUNT : usize = 2;
+const SPRITE_COLOR_JITTER : i32 = 20;
+
+// Every biome covers the part of the map that is closer to its center than to the others.
+const BIOME_REGION_COUNT : usize = 8;
+
+// Density curve is the sum of waves along the road, it is 0 where there is no scenery and 1 at its peak.
+const DENSITY_WAVE_COUNT : usize = 2;
+const DENSITY_WAVE_PERIOD : (f32, f32) = (30.0, 120.0);
+// Scenery never stands closer to the road edge.
+const MIN_ROADSIDE_GAP : f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SceneryKind {
+    Pine,
+    Oak,
+    Bush,
+    Cactus,
+    Rock,
+    Haystack,
+    Barn,
+    House,
+    LampPost,
+    Sign
+}
+
+const SCENERY_KINDS : [SceneryKind; 10] = [
+    SceneryKind::Pine, SceneryKind::Oak, SceneryKind::Bush, SceneryKind::Cactus, SceneryKind::Rock,
+    SceneryKind::Haystack, SceneryKind::Barn, SceneryKind::House, SceneryKind::LampPost, SceneryKind::Sign
+];
+
+impl SceneryKind {
+    fn get_file_name(&self) -> &'static str {
+        match self {
+            SceneryKind::Pine => { "pine" }
+            SceneryKind::Oak => { "oak" }
+            SceneryKind::Bush => { "bush" }
+            SceneryKind::Cactus => { "cactus" }
+            SceneryKind::Rock => { "rock" }
+            SceneryKind::Haystack => { "haystack" }
+            SceneryKind::Barn => { "barn" }
+            SceneryKind::House => { "house" }
+            SceneryKind::LampPost => { "lamp_post" }
+            SceneryKind::Sign => { "sign" }
+        }
+    }
+
+    // Width and height in road units.
+    fn get_size(&self) -> (f32, f32) {
+        match self {
+            SceneryKind::Pine => { (0.6, 1.4) }
+            SceneryKind::Oak => { (0.8, 1.0) }
+            SceneryKind::Bush => { (0.35, 0.2) }
+            SceneryKind::Cactus => { (0.25, 0.55) }
+            SceneryKind::Rock => { (0.3, 0.18) }
+            SceneryKind::Haystack => { (0.35, 0.25) }
+            SceneryKind::Barn => { (1.6, 1.1) }
+            SceneryKind::House => { (1.2, 0.9) }
+            SceneryKind::LampPost => { (0.12, 0.9) }
+            SceneryKind::Sign => { (1.0, 0.7) }
+        }
+    }
+
+    fn generate_sprite(&self, rng : &mut StdRng) -> BillboardFactory {
+        let (width, height) = self.get_size();
+        let mut sketch = Sketch::new(width, height);
+        let mut color = |r : i32, g : i32, b : i32| Sketch::jitter_color(r, g, b, SPRITE_COLOR_JITTER, rng);
+
+        match self {
+            SceneryKind::Pine => {
+                sketch.fill_rect(0.44, 0.85, 0.56, 1.0, color(90, 60, 30));
+                let needles = color(20, 90, 40);
+                sketch.fill_triangle(0.5, 0.35, 0.0, 1.0, 0.9, needles);
+                sketch.fill_triangle(0.5, 0.15, 0.1, 0.9, 0.65, needles);
+                sketch.fill_triangle(0.5, 0.0, 0.2, 0.8, 0.4, needles);
+            }
+            SceneryKind::Oak => {
+                sketch.fill_rect(0.42, 0.55, 0.58, 1.0, color(100, 70, 40));
+                sketch.fill_ellipse(0.5, 0.35, 0.5, 0.35, color(40, 120, 30));
+                sketch.fill_ellipse(0.4, 0.28, 0.25, 0.18, color(70, 150, 50));
+            }
+            SceneryKind::Bush => {
+                sketch.fill_ellipse(0.5, 0.6, 0.5, 0.4, color(50, 110, 40));
+                sketch.fill_ellipse(0.35, 0.5, 0.25, 0.3, color(70, 130, 50));
+            }
+            SceneryKind::Cactus => {
+                let skin = color(60, 140, 60);
+                sketch.fill_rect(0.38, 0.08, 0.62, 1.0, skin);
+                sketch.fill_ellipse(0.5, 0.08, 0.12, 0.06, skin);
+                sketch.fill_rect(0.1, 0.45, 0.4, 0.55, skin);
+                sketch.fill_rect(0.1, 0.2, 0.24, 0.55, skin);
+                sketch.fill_rect(0.6, 0.35, 0.9, 0.45, skin);
+                sketch.fill_rect(0.76, 0.12, 0.9, 0.45, skin);
+            }
+            SceneryKind::Rock => {
+                sketch.fill_ellipse(0.5, 0.8, 0.5, 0.7, color(120, 115, 110));
+                sketch.fill_ellipse(0.4, 0.55, 0.2, 0.2, color(150, 145, 140));
+            }
+            SceneryKind::Haystack => {
+                sketch.fill_ellipse(0.5, 1.0, 0.5, 0.95, color(210, 180, 70));
+                sketch.fill_rect(0.04, 0.55, 0.96, 0.62, color(170, 140, 50));
+            }
+            SceneryKind::Barn => {
+                sketch.fill_rect(0.05, 0.35, 0.95, 1.0, color(160, 40, 30));
+                sketch.fill_triangle(0.5, 0.0, 0.0, 1.0, 0.38, color(70, 40, 30));
+                sketch.fill_rect(0.38, 0.6, 0.62, 1.0, color(90, 25, 20));
+            }
+            SceneryKind::House => {
+                sketch.fill_rect(0.1, 0.4, 0.9, 1.0, color(210, 200, 170));
+                sketch.fill_triangle(0.5, 0.0, 0.0, 1.0, 0.42, color(130, 50, 40));
+                sketch.fill_rect(0.2, 0.55, 0.4, 0.72, color(90, 150, 200));
+                sketch.fill_rect(0.6, 0.65, 0.75, 1.0, color(100, 70, 40));
+            }
+            SceneryKind::LampPost => {
+                sketch.fill_rect(0.35, 0.1, 0.65, 1.0, color(80, 80, 80));
+                sketch.fill_rect(0.0, 0.04, 1.0, 0.1, color(60, 60, 60));
+                sketch.fill_ellipse(0.5, 0.12, 0.4, 0.04, color(255, 230, 150));
+            }
+            SceneryKind::Sign => {
+                let poles = color(90, 90, 90);
+                sketch.fill_rect(0.15, 0.5, 0.22, 1.0, poles);
+                sketch.fill_rect(0.78, 0.5, 0.85, 1.0, poles);
+                sketch.fill_rect(0.0, 0.05, 1.0, 0.55, color(230, 230, 230));
+                sketch.fill_rect(0.08, 0.15, 0.92, 0.3, color(200, 60, 40));
+                sketch.fill_rect(0.08, 0.35, 0.6, 0.45, color(40, 80, 160));
+            }
+        }
+
+        sketch.finish()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Biome {
+    Forest,
+    Desert,
+    Farmland,
+    Outskirts
+}
+
+const BIOMES : [Biome; 4] = [Biome::Forest, Biome::Desert, Biome::Farmland, Biome::Outskirts];
+
+struct BiomePalette {
+    kinds : &'static [(SceneryKind, f32)],
+    // Clusters per road unit where the density curve is at its peak.
+    cluster_density : f32,
+    cluster_size : (usize, usize),
+    // Distance between the cluster items and its center along the road and sideways.
+    cluster_spread : f32,
+    max_roadside_gap : f32,
+    ground_colors : (Rgb<u8>, Rgb<u8>)
+}
+
+impl Biome {
+    fn get_palette(&self) -> BiomePalette {
+        match self {
+            Biome::Forest => { BiomePalette {
+                kinds : &[(SceneryKind::Pine, 5.0), (SceneryKind::Oak, 3.0), (SceneryKind::Bush, 2.0), (SceneryKind::Rock, 1.0)],
+                cluster_density : 0.25,
+                cluster_size : (3, 8),
+                cluster_spread : 2.0,
+                max_roadside_gap : 1.5,
+                ground_colors : (Rgb([0, 100, 0]), Rgb([0, 120, 0]))
+            } }
+            Biome::Desert => { BiomePalette {
+                kinds : &[(SceneryKind::Cactus, 4.0), (SceneryKind::Rock, 3.0), (SceneryKind::Bush, 1.0)],
+                cluster_density : 0.12,
+                cluster_size : (1, 3),
+                cluster_spread : 1.5,
+                max_roadside_gap : 3.0,
+                ground_colors : (Rgb([200, 170, 100]), Rgb([210, 180, 110]))
+            } }
+            Biome::Farmland => { BiomePalette {
+                kinds : &[(SceneryKind::Haystack, 3.0), (SceneryKind::Oak, 2.0), (SceneryKind::Bush, 2.0), (SceneryKind::Barn, 1.0)],
+                cluster_density : 0.15,
+                cluster_size : (1, 4),
+                cluster_spread : 2.0,
+                max_roadside_gap : 2.5,
+                ground_colors : (Rgb([90, 130, 30]), Rgb([110, 145, 40]))
+            } }
+            Biome::Outskirts => { BiomePalette {
+                kinds : &[(SceneryKind::House, 4.0), (SceneryKind::LampPost, 3.0), (SceneryKind::Sign, 1.0), (SceneryKind::Bush, 1.0)],
+                cluster_density : 0.2,
+                cluster_size : (2, 5),
+                cluster_spread : 1.5,
+                max_roadside_gap : 1.0,
+                ground_colors : (Rgb([80, 110, 60]), Rgb([95, 125, 70]))
+            } }
+        }
+    }
+
+    pub fn get_ground_colors(&self) -> (Rgb<u8>, Rgb<u8>) {
+        self.get_palette().ground_colors
+    }
+}
+
+// Neighbouring roads lie in the same region, so they share the biome.
+pub struct BiomeMap {
+    regions : Vec<(Vec2, Biome)>
+}
+
+impl BiomeMap {
+    pub fn generate(rng : &mut StdRng, size : &IVec2) -> BiomeMap {
+        let regions = (0..BIOME_REGION_COUNT)
+        .map(|_| {
+            let center = Vec2::new(rng.gen_range(0.0, size.x as f32), rng.gen_range(0.0, size.y as f32));
+            (center, BIOMES[rng.gen_range(0, BIOMES.len())])
+        })
+        .collect();
+
+        BiomeMap { regions }
+    }
+
+    pub fn get_biome(&self, position : &Vec2) -> Biome {
+        self.regions.iter()
+        .min_by(|a, b| (&a.0 - position).sqr_len().partial_cmp(&(&b.0 - position).sqr_len()).unwrap())
+        .map(|(_, biome)| *biome)
+        .unwrap()
+    }
+}
+
+// Sprites of every scenery kind, shared by all the roads.
+pub struct Scenery {
+    factories : Vec<(SceneryKind, Vec<BillboardFactory>)>
+}
+
+impl Scenery {
+    // Sprites are loaded from scenery/<kind>.png when there is such file, generated otherwise.
+    pub fn generate(rng : &mut StdRng) -> Scenery {
+        let factories = SCENERY_KINDS.iter()
+        .map(|kind| {
+            let variants = match Game::find_image_rgba(&format!("scenery/{}.png", kind.get_file_name())) {
+                Some(sprite) => { vec![BillboardFactory::from_image(&sprite)] }
+                None => { (0..SPRITE_VARIANT_COUNT).map(|_| kind.generate_sprite(rng)).collect() }
+            };
+            (*kind, variants)
+        })
+        .collect();
+
+        Scenery { factories }
+    }
+
+    fn get_variants(&self, kind : SceneryKind) -> &[BillboardFactory] {
+        &self.factories.iter().find(|(factory_kind, _)| *factory_kind == kind).unwrap().1
+    }
+
+    // Clusters are scattered along the whole road and never stand on its surface.
+    pub fn place(&self, biome : Biome, track_data : &TrackData, length : f32, rng : &mut StdRng) -> Billboards {
+        let palette = biome.get_palette();
+        let waves : Vec<(f32, f32)> = (0..DENSITY_WAVE_COUNT)
+        .map(|_| (rng.gen_range(DENSITY_WAVE_PERIOD.0, DENSITY_WAVE_PERIOD.1), rng.gen_range(0.0, 1.0)))
+        .collect();
+        let get_density = |distance : f32| {
+            let wave_sum = waves.iter().fold(0.0, |sum, (period, phase)| sum + (2.0 * PI * (distance / period + phase)).sin());
+            Math::min(Math::max(0.5 + 0.5 * wave_sum / waves.len() as f32, 0.0), 1.0)
+        };
+
+        let mut items = Vec::new();
+        let mut distance = rng.gen_range(0.0, 1.0 / palette.cluster_density);
+        while distance < length {
+            if rng.gen_bool(get_density(distance) as f64) {
+                self.place_cluster(&palette, distance, track_data, length, rng, &mut items);
+            }
+            distance += rng.gen_range(0.5, 1.5) / palette.cluster_density;
+        }
+
+        let mut billboards = Billboards::new();
+        billboards.add_static(items);
+        billboards
+    }
+
+    fn place_cluster(&self, palette : &BiomePalette, distance : f32, track_data : &TrackData, length : f32, rng : &mut StdRng, items : &mut Vec<Billboard>) {
+        let side = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        let gap = rng.gen_range(MIN_ROADSIDE_GAP, palette.max_roadside_gap);
+        let total_weight = palette.kinds.iter().fold(0.0, |sum, (_, weight)| sum + weight);
+
+        for _ in 0..rng.gen_range(palette.cluster_size.0, palette.cluster_size.1 + 1) {
+            let item_distance = distance + rng.gen_range(-palette.cluster_spread, palette.cluster_spread);
+            if item_distance < 0.0 || item_distance > length { continue; }
+
+            let mut choice = rng.gen_range(0.0, total_weight);
+            let kind = palette.kinds.iter().find(|(_, weight)| { choice -= weight; choice < 0.0 }).unwrap_or(&palette.kinds[0]).0;
+            let variants = self.get_variants(kind);
+            let factory = &variants[rng.gen_range(0, variants.len())];
+
+            let (left, right) = track_data.get_road_borders(item_distance).unwrap_or((0.0, 0.0));
+            let edge = if side > 0.0 { right } else { left };
+            let offset = edge + side * (gap + kind.get_size().0 * 0.5 + rng.gen_range(0.0, palette.cluster_spread));
+            items.push(factory.construct(item_distance, offset));
+        }
+    }
+}
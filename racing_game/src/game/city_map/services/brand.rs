@@ -0,0 +1,136 @@
+use std::rc::Rc;
+
+use rand::{Rng, rngs::StdRng};
+use image::{RgbaImage, Rgba};
+
+use super::ServiceType;
+use crate::Game;
+use crate::engine::common::Math;
+use crate::engine::ui::font::Font;
+
+const NAME_SYLLABLES : [&str; 16] = [
+    "KO", "RA", "MI", "TO", "VEL", "SAN", "LU", "DOR",
+    "BE", "NO", "TA", "RI", "GAL", "MAR", "ZE", "OK"
+];
+const NAME_SYLLABLE_COUNT : (usize, usize) = (2, 4);
+// Random names run out for many services, then they get numbers.
+const MAX_NAME_COLLISIONS : u32 = 100;
+
+const LOGO_SIZE : u32 = 24;
+// Logos stand left of the service names in the lists.
+const LOGO_MAX_WIDTH : u32 = 36;
+const LOGO_PADDING : u32 = 6;
+// Badge colors are kept dark enough for the white font.
+const BADGE_COLOR_RANGE : (u8, u8) = (40, 170);
+
+// Name and logo shared by all the cities the service is present in.
+#[derive(Clone)]
+pub struct Brand {
+    pub name : String,
+    pub logo : Rc<RgbaImage>
+}
+
+impl Brand {
+    // Names are unique among the services of one type.
+    pub fn generate_all(service_type : ServiceType, count : usize, font : &Font, rng : &mut StdRng) -> Vec<Brand> {
+        let mut names : Vec<String> = Vec::new();
+        let mut collisions = 0;
+        while names.len() < count {
+            let mut name = Self::generate_name(service_type, rng);
+            // Random names have no digits, so the numbered one is unique.
+            if collisions >= MAX_NAME_COLLISIONS { name = format!("{} {}", name, names.len() + 1); }
+            if names.contains(&name) {
+                collisions += 1;
+            } else {
+                names.push(name);
+                collisions = 0;
+            }
+        }
+
+        names.into_iter()
+        .enumerate()
+        .map(|(id, name)| {
+            let logo = match Game::find_image_rgba(&format!("logos/{}/logo{}.png", Self::get_logo_dir(service_type), id)) {
+                Some(logo) => { logo }
+                None => { Self::generate_logo(&name, font, rng) }
+            };
+            Brand { name, logo : Rc::from(logo) }
+        })
+        .collect()
+    }
+
+    fn get_logo_dir(service_type : ServiceType) -> &'static str {
+        match service_type {
+            ServiceType::GasStation => { "gas_stations" }
+            ServiceType::Hostel => { "hostels" }
+            ServiceType::RepairStation => { "repair_stations" }
+            ServiceType::Shop => { "shops" }
+            ServiceType::JobBoard => { "job_boards" }
+        }
+    }
+
+    fn get_name_suffixes(service_type : ServiceType) -> &'static [&'static str] {
+        match service_type {
+            ServiceType::GasStation => { &["GAS", "FUEL", "PETROL", "OIL"] }
+            ServiceType::Hostel => { &["INN", "MOTEL", "HOSTEL", "LODGE"] }
+            ServiceType::RepairStation => { &["AUTO", "MOTORS", "GARAGE", "SERVICE"] }
+            ServiceType::Shop => { &["MART", "STORE", "SHOP", "MARKET"] }
+            ServiceType::JobBoard => { &["CARGO", "FREIGHT", "LOGISTICS", "DELIVERY"] }
+        }
+    }
+
+    fn generate_name(service_type : ServiceType, rng : &mut StdRng) -> String {
+        let syllable_count = rng.gen_range(NAME_SYLLABLE_COUNT.0, NAME_SYLLABLE_COUNT.1);
+        let mut name : String = (0..syllable_count).map(|_| NAME_SYLLABLES[rng.gen_range(0, NAME_SYLLABLES.len())]).collect();
+        let suffixes = Self::get_name_suffixes(service_type);
+        name.push(' ');
+        name.push_str(suffixes[rng.gen_range(0, suffixes.len())]);
+        name
+    }
+
+    // Whole name if it fits the badge, otherwise initials of its words, as many as fit.
+    fn get_logo_text(name : &str, font : &Font) -> String {
+        let max_width = LOGO_MAX_WIDTH - 2 * LOGO_PADDING;
+        if font.get_width(name) <= max_width { return String::from(name); }
+
+        let mut initials : String = name.split_whitespace().filter_map(|word| word.chars().next()).collect();
+        while initials.chars().count() > 1 && font.get_width(&initials) > max_width { initials.pop(); }
+        initials
+    }
+
+    // Rounded badge of random color with the name or its abbreviation.
+    fn generate_logo(name : &str, font : &Font, rng : &mut StdRng) -> RgbaImage {
+        let mut color = [0; 3];
+        for channel in &mut color { *channel = rng.gen_range(BADGE_COLOR_RANGE.0, BADGE_COLOR_RANGE.1); }
+        let border_color = Rgba([color[0] / 2, color[1] / 2, color[2] / 2, 255]);
+        let color = Rgba([color[0], color[1], color[2], 255]);
+
+        let text = Self::get_logo_text(&font.to_printable(name), font);
+        let text_width = font.get_width(&text);
+        let width = Math::max(text_width + 2 * LOGO_PADDING, LOGO_SIZE);
+        let mut logo = RgbaImage::new(width, LOGO_SIZE);
+        // Badge is a line between the centers of its round ends widened by the radius.
+        let radius = LOGO_SIZE as f32 * 0.5;
+        for (x, y, pixel) in logo.enumerate_pixels_mut() {
+            let closest_x = (x as f32 + 0.5).clamp(radius, width as f32 - radius);
+            let distance = ((x as f32 + 0.5 - closest_x).powi(2) + (y as f32 + 0.5 - radius).powi(2)).sqrt();
+            if distance < radius - 1.5 {
+                *pixel = color;
+            } else if distance < radius {
+                *pixel = border_color;
+            }
+        }
+
+        let mut offset_x = (width - text_width) / 2;
+        for symbol in text.chars() {
+            let symbol = font.get_symbol(symbol);
+            let offset_y = (LOGO_SIZE - symbol.height()) / 2;
+            for (x, y, pixel) in symbol.enumerate_pixels() {
+                if pixel[3] > 0 { logo.put_pixel(x + offset_x, y + offset_y, *pixel); }
+            }
+            offset_x += symbol.width();
+        }
+
+        logo
+    }
+}
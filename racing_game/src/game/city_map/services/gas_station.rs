@@ -4,43 +4,104 @@ use rand::{Rng, rngs::StdRng};
 use image::{RgbaImage};
 
 use super::*;
-use crate::game::{Percent, player::Player};
+use crate::game::{Percent, player::{Player, OIL_PER_LITER}};
+use crate::game::save::parse_value;
+use crate::engine::common::Math;
 
 #[readonly::make]
 #[derive(Clone)]
 pub struct GasStation {
-    pub logo : Rc<RgbaImage>,
+    brand : Brand,
     pub gas_cost : f32,
-    pub discount : Percent
+    pub discount : Percent,
+    pricing : Pricing
 }   
 
+// Demand added by every liter of bought gas.
+const GAS_DEMAND : f32 = 0.005;
+// Liter of oil costs as much as this many liters of gas.
+const OIL_COST_FACTOR : f32 = 0.8;
+const OIL_DEMAND : f32 = 0.02;
+
 impl GasStation {
-    pub fn generate(logo : RgbaImage, rng : &mut StdRng) -> GasStation {
+    pub fn generate(brand : Brand, rng : &mut StdRng) -> GasStation {
         let gas_cost = rng.gen_range(5.0, 15.0);
-        GasStation { logo : Rc::from(logo), gas_cost, discount : Percent(0.0) }
+        GasStation { brand, gas_cost, discount : Percent(0.0), pricing : Pricing::generate(rng) }
+    }
+
+    pub fn get_gas_cost(&self, price_level : f32) -> f32 {
+        self.pricing.get_price(self.gas_cost, price_level)
+    }
+
+    // Amount is limited by both the wallet and the free space in the tank.
+    pub fn get_max_gas_amount(&self, player : &Player, price_level : f32) -> u32 {
+        let affordable = (player.money / self.get_gas_cost(price_level)).floor();
+        let fits_in_tank = (player.max_gas_level - player.gas_level).floor();
+        Math::max(Math::min(affordable, fits_in_tank), 0.0) as u32
     }
 
-    pub fn get_max_gas_amount(&self, money : f32) -> u32 {
-        (money / self.gas_cost).floor() as u32
+    pub fn get_cost(&self, amount : u32, price_level : f32) -> f32 {
+        return self.get_gas_cost(price_level) * amount as f32;
     }
 
-    pub fn get_cost(&self, amount : u32) -> f32 {
-        return self.gas_cost * amount as f32;
+    pub fn get_oil_cost(&self, price_level : f32) -> f32 {
+        self.pricing.get_price(self.gas_cost * OIL_COST_FACTOR, price_level)
     }
 
-    pub fn buy_gas(&mut self, amount : u32, player : &mut Player) {
-        player.money -= self.gas_cost * amount as f32;
+    // Oil is sold in whole liters, so the engine is never overfilled.
+    pub fn get_max_oil_amount(&self, player : &Player, price_level : f32) -> u32 {
+        let affordable = (player.money / self.get_oil_cost(price_level)).floor();
+        let fits_in_engine = ((player.max_oil_level - player.oil_level) / OIL_PER_LITER).floor();
+        Math::max(Math::min(affordable, fits_in_engine), 0.0) as u32
+    }
+
+    pub fn get_oil_total_cost(&self, amount : u32, price_level : f32) -> f32 {
+        self.get_oil_cost(price_level) * amount as f32
+    }
+
+    pub fn buy_gas(&mut self, amount : u32, player : &mut Player, price_level : f32) {
+        let amount = Math::min(amount, self.get_max_gas_amount(player, price_level));
+        player.money -= self.get_cost(amount, price_level);
         player.gas_level += amount as f32;
+        self.pricing.add_demand(amount as f32 * GAS_DEMAND);
         self.discount.0 += amount as f32 * 0.1;
         if self.discount.0 > 50.0 { self.discount.0 = 50.0; }
+    }
 
-        println!("money : {} gas : {} discount : {}", player.money, player.gas_level, self.discount.0);
+    pub fn buy_oil(&mut self, amount : u32, player : &mut Player, price_level : f32) {
+        let amount = Math::min(amount, self.get_max_oil_amount(player, price_level));
+        player.money -= self.get_oil_total_cost(amount, price_level);
+        player.oil_level += amount as f32 * OIL_PER_LITER;
+        self.pricing.add_demand(amount as f32 * OIL_DEMAND);
     }
 }
 
 impl Service for GasStation { 
     fn get_logo(&self) -> Rc<RgbaImage> {
-        self.logo.clone()
+        self.brand.logo.clone()
+    }
+
+    fn get_name(&self) -> &str {
+        &self.brand.name
+    }
+
+    fn get_price_summary(&self, price_level : f32) -> String {
+        format!("GAS {}$", self.get_gas_cost(price_level))
+    }
+
+    fn pass_time(&mut self, minutes : f32) {
+        self.pricing.pass_time(minutes);
+    }
+
+    fn save_state(&self) -> Vec<String> {
+        let mut state = vec![self.discount.0.to_string()];
+        state.extend(self.pricing.save_state());
+        state
+    }
+
+    fn load_state(&mut self, state : &[String]) -> Result<(), String> {
+        self.discount = Percent(parse_value(state, 0)?);
+        self.pricing.load_state(&state[1..])
     }
 
     fn get_ref_type(&self) -> ServiceType { ServiceType::GasStation }
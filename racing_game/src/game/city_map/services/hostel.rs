@@ -6,34 +6,87 @@ use image::RgbaImage;
 use super::*;
 use crate::game::Time;
 
+// Rooms are rented for one of these times, longer stays are cheaper per hour.
+const RENT_TIMES : [Time; 6] = [
+    Time { hr : 1, min : 0 }, Time { hr : 2, min : 0 }, Time { hr : 3, min : 30 },
+    Time { hr : 6, min : 0 }, Time { hr : 8, min : 0 }, Time { hr : 10, min : 0 }
+];
+const HOURLY_COST : (f32, f32) = (4.0, 8.0);
+const LONG_STAY_DISCOUNT_PER_HOUR : f32 = 0.03;
+// Demand added by every stay.
+const REST_DEMAND : f32 = 0.1;
+
 pub struct RoomRemoveOption {
     pub time : Time,
     pub cost : f32
 }
 
 pub struct Hostel {
-    logo : Rc<RgbaImage>,
-    pub options : Vec<RoomRemoveOption>
+    brand : Brand,
+    pub options : Vec<RoomRemoveOption>,
+    pricing : Pricing
 }
 
 impl Hostel {
-    pub fn generate(logo : RgbaImage, rng : &mut StdRng) -> Hostel {
-        let mut options = Vec::new();
-        options.push(RoomRemoveOption { time : Time::new(1, 30), cost : 10.0 });
-        options.push(RoomRemoveOption { time : Time::new(1, 00), cost : 15.0 });
-        options.push(RoomRemoveOption { time : Time::new(2, 50), cost : 20.0 });
+    pub fn generate(brand : Brand, rng : &mut StdRng) -> Hostel {
+        let hourly_cost = rng.gen_range(HOURLY_COST.0, HOURLY_COST.1);
+        let option_count = rng.gen_range(2, 5);
+        let mut rent_time_ids : Vec<usize> = (0..RENT_TIMES.len()).collect();
+        while rent_time_ids.len() > option_count { rent_time_ids.remove(rng.gen_range(0, rent_time_ids.len())); }
+
+        let options = rent_time_ids.into_iter().map(|id| {
+            let time = RENT_TIMES[id].clone();
+            let hours = time.to_minutes() as f32 / 60.0;
+            let cost = (hours * hourly_cost * (1.0 - hours * LONG_STAY_DISCOUNT_PER_HOUR)).round();
+            RoomRemoveOption { time, cost }
+        })
+        .collect();
 
-        Hostel { logo : Rc::from(logo), options }
+        Hostel { brand, options, pricing : Pricing::generate(rng) }
     }
 
-    pub fn rest(&mut self, option_id : u32, player : &mut Player) {
-        player.money -= self.options[option_id as usize].cost;
+    pub fn get_rest_cost(&self, option_id : u32, price_level : f32) -> f32 {
+        self.pricing.get_price(self.options[option_id as usize].cost, price_level)
+    }
+
+    pub fn get_rest_minutes(&self, option_id : u32) -> f32 {
+        self.options[option_id as usize].time.to_minutes() as f32
+    }
+
+    pub fn rest(&mut self, option_id : u32, player : &mut Player, price_level : f32) {
+        let cost = self.get_rest_cost(option_id, price_level);
+        if player.money < cost { return; }
+
+        player.money -= cost;
+        player.rest(self.get_rest_minutes(option_id));
+        self.pricing.add_demand(REST_DEMAND);
     }
 }
 
 impl Service for Hostel {
     fn get_logo(&self) -> Rc<RgbaImage> {
-        self.logo.clone()
+        self.brand.logo.clone()
+    }
+
+    fn get_name(&self) -> &str {
+        &self.brand.name
+    }
+
+    fn get_price_summary(&self, price_level : f32) -> String {
+        let min_cost = (0..self.options.len()).map(|id| self.get_rest_cost(id as u32, price_level)).fold(f32::MAX, f32::min);
+        format!("ROOM FROM {}$", min_cost)
+    }
+
+    fn pass_time(&mut self, minutes : f32) {
+        self.pricing.pass_time(minutes);
+    }
+
+    fn save_state(&self) -> Vec<String> {
+        self.pricing.save_state()
+    }
+
+    fn load_state(&mut self, state : &[String]) -> Result<(), String> {
+        self.pricing.load_state(state)
     }
 
     fn get_ref_type(&self) -> ServiceType { ServiceType::Hostel }
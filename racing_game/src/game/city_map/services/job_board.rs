@@ -0,0 +1,111 @@
+use std::rc::Rc;
+
+use rand::{Rng, rngs::StdRng};
+use image::RgbaImage;
+
+use super::*;
+use crate::game::save::parse_value;
+
+const CONTRACT_COUNT : usize = 4;
+pub const MAX_CARGO_COUNT : usize = 3;
+// Cargo is fragile when it takes more damage than the car.
+pub const FRAGILE_LEVEL : f32 = 1.0;
+
+// Reward and deadline depend on the distance from the city where the contract is taken.
+#[derive(Clone)]
+pub struct Contract {
+    pub destination_city_id : usize,
+    reward_per_distance : f32,
+    minutes_per_distance : f32,
+    pub fragility : f32
+}
+
+impl Contract {
+    pub fn get_reward(&self, distance : f32) -> f32 {
+        (self.reward_per_distance * distance).floor()
+    }
+
+    pub fn get_minutes(&self, distance : f32) -> f32 {
+        self.minutes_per_distance * distance
+    }
+}
+
+// Taken contract, deadline is the clock time.
+#[derive(Clone)]
+pub struct Cargo {
+    pub destination_city_id : usize,
+    pub reward : f32,
+    pub deadline : f32,
+    pub fragility : f32,
+    pub condition : Percent
+}
+
+impl Cargo {
+    // Damaged cargo is paid by its condition, late cargo isn't paid at all.
+    pub fn get_payment(&self, now : f32) -> f32 {
+        if now > self.deadline { 0.0 } else { (self.reward * self.condition.to_norm()).floor() }
+    }
+}
+
+pub struct JobBoard {
+    brand : Brand,
+    pub contracts : Vec<Contract>,
+    taken : Vec<bool>
+}
+
+impl JobBoard {
+    pub fn generate(brand : Brand, city_count : usize, rng : &mut StdRng) -> JobBoard {
+        let contracts = (0..CONTRACT_COUNT).map(|_| Contract {
+            destination_city_id : rng.gen_range(0, city_count),
+            reward_per_distance : rng.gen_range(0.3, 0.8),
+            minutes_per_distance : rng.gen_range(3.0, 8.0),
+            fragility : rng.gen_range(0.5, 2.0)
+        })
+        .collect();
+
+        JobBoard { brand, contracts, taken : vec![false; CONTRACT_COUNT] }
+    }
+
+    // Same board is shared by several cities, so contracts to the current one are hidden.
+    pub fn get_offers(&self, current_city_id : usize) -> Vec<usize> {
+        (0..self.contracts.len())
+        .filter(|&id| !self.taken[id] && self.contracts[id].destination_city_id != current_city_id)
+        .collect()
+    }
+
+    pub fn take_contract(&mut self, contract_id : usize, cargo : Cargo, player : &mut Player) {
+        if self.taken[contract_id] || player.cargo.len() >= MAX_CARGO_COUNT { return; }
+
+        self.taken[contract_id] = true;
+        player.cargo.push(cargo);
+    }
+}
+
+impl Service for JobBoard {
+    fn get_logo(&self) -> Rc<RgbaImage> {
+        self.brand.logo.clone()
+    }
+
+    fn get_name(&self) -> &str {
+        &self.brand.name
+    }
+
+    fn get_price_summary(&self, _price_level : f32) -> String {
+        format!("{} CONTRACTS", self.taken.iter().filter(|&&taken| !taken).count())
+    }
+
+    fn save_state(&self) -> Vec<String> {
+        if !self.taken.contains(&true) { return Vec::new(); }
+        self.taken.iter().map(|&taken| (taken as u32).to_string()).collect()
+    }
+
+    fn load_state(&mut self, state : &[String]) -> Result<(), String> {
+        for id in 0..self.taken.len() {
+            self.taken[id] = parse_value::<u32>(state, id)? != 0;
+        }
+        Ok(())
+    }
+
+    fn get_ref_type(&self) -> ServiceType { ServiceType::JobBoard }
+    fn get_type() -> ServiceType { ServiceType::JobBoard }
+}
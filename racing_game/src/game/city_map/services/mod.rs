@@ -9,26 +9,44 @@ use crate::Game;
 use crate::game::Percent;
 use crate::game::player::Player;
 use crate::game::ride::car::*;
+use crate::game::save::*;
 
 mod gas_station;
 mod hostel;
 mod repair_station;
 mod shop;
+mod job_board;
+mod pricing;
+mod brand;
 
 pub use gas_station::*;
 pub use hostel::*;
 pub use repair_station::*;
 pub use shop::*;
+pub use job_board::*;
+pub use pricing::*;
+pub use brand::*;
 
 pub enum ServiceAction{
     BuyGas(u32),
+    BuyOil(u32),
     RestInHostel(u32),
+    DiagnoseCar,
     FixCarSystem(CarSystem, Percent),
-    BuyProduct(usize)
+    BuyProduct(usize),
+    TakeContract(usize, Cargo)
 }
 
 pub trait Service {
     fn get_logo(&self) -> Rc<RgbaImage>;
+    fn get_name(&self) -> &str;
+    // Short line shown next to the logo in the list of services.
+    fn get_price_summary(&self, price_level : f32) -> String;
+    fn pass_time(&mut self, _minutes : f32) { }
+
+    // State that changes during the game, everything else is regenerated from the seed.
+    fn save_state(&self) -> Vec<String> { Vec::new() }
+    fn load_state(&mut self, _state : &[String]) -> Result<(), String> { Ok(()) }
 
     fn get_type() -> ServiceType where Self : Sized;
     fn get_ref_type(&self) -> ServiceType;
@@ -37,12 +55,19 @@ pub trait Service {
 #[derive(Clone, Copy)]
 pub struct ServiceId(pub usize);
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum ServiceType {
     GasStation,
     Hostel,
     RepairStation,
-    Shop
+    Shop,
+    JobBoard
+}
+
+impl ServiceType {
+    pub fn all() -> [ServiceType; 5] {
+        [ServiceType::GasStation, ServiceType::Hostel, ServiceType::RepairStation, ServiceType::Shop, ServiceType::JobBoard]
+    }
 }
 
 pub struct CityServicesSubset {
@@ -63,44 +88,34 @@ pub struct ServicesSubsetProperties {
     pub gas_station_count : usize,
     pub hostel_count : usize,
     pub repair_station_count : usize,
-    pub shop_count : usize
+    pub shop_count : usize,
+    pub job_board_count : usize
 }
 
-impl Services {
-    pub fn generate(rng : &mut StdRng) -> Services {
-        let mut gas_stations = Vec::new();
-        for i in 0..7 {
-            let gs_logo = Game::load_image_rgba(&*format!("logos/gas_stations/logo{}.png", i));
-            let gs = GasStation::generate(gs_logo, rng);
-            gas_stations.push(Box::<dyn Service>::from(Box::from(gs)));
-        }
+// Makes a service of the brand for the map with the given city count.
+type ServiceGenerator = fn(Brand, usize, &mut StdRng) -> Box<dyn Service>;
 
-        let mut hostels = Vec::new();
-        for i in 0..7 {
-            let h_logo = Game::load_image_rgba(&*format!("logos/hostels/logo{}.png", i));
-            let h = Hostel::generate(h_logo, rng);
-            hostels.push(Box::<dyn Service>::from(Box::from(h)));
-        }
-
-        let mut repair_stations = Vec::new();
-        for i in 0..7 {
-            let rs_logo = Game::load_image_rgba(&*format!("logos/hostels/logo{}.png", i));
-            let rs = RepairStation::generate(rs_logo, rng);
-            repair_stations.push(Box::<dyn Service>::from(Box::from(rs)));
-        }
-
-        let mut shops = Vec::new();
-        for i in 0..7 {
-            let sh_logo = Game::load_image_rgba(&*format!("logos/hostels/logo{}.png", i));
-            let sh = Shop::generate(sh_logo, rng);
-            shops.push(Box::<dyn Service>::from(Box::from(sh)));
-        }
+impl Services {
+    // Job boards offer contracts to any city of the map.
+    pub fn generate(rng : &mut StdRng, city_count : usize, service_count : usize) -> Services {
+        let font = Game::load_font();
+
+        // Job boards need the city count to pick cargo destinations.
+        let generators : [(ServiceType, ServiceGenerator); 5] = [
+            (ServiceType::GasStation, |brand, _, rng| Box::from(GasStation::generate(brand, rng))),
+            (ServiceType::Hostel, |brand, _, rng| Box::from(Hostel::generate(brand, rng))),
+            (ServiceType::RepairStation, |brand, _, rng| Box::from(RepairStation::generate(brand, rng))),
+            (ServiceType::Shop, |brand, _, rng| Box::from(Shop::generate(brand, rng))),
+            (ServiceType::JobBoard, |brand, city_count, rng| Box::from(JobBoard::generate(brand, city_count, rng)))
+        ];
 
         let mut services = HashMap::new();
-        services.insert(ServiceType::GasStation, gas_stations);
-        services.insert(ServiceType::Hostel, hostels);
-        services.insert(ServiceType::RepairStation, repair_stations);
-        services.insert(ServiceType::Shop, shops);
+        for (service_type, generate) in generators.iter() {
+            let services_of_type = Brand::generate_all(*service_type, service_count, &font, rng).into_iter()
+            .map(|brand| generate(brand, city_count, rng))
+            .collect();
+            services.insert(*service_type, services_of_type);
+        }
 
         Services { services }
     }
@@ -129,19 +144,56 @@ impl Services {
         self.generate_subset_concrete_service(&mut subset, ServiceType::Hostel,         properties.hostel_count,            rng);
         self.generate_subset_concrete_service(&mut subset, ServiceType::RepairStation,  properties.repair_station_count,    rng);
         self.generate_subset_concrete_service(&mut subset, ServiceType::Shop,           properties.shop_count,              rng);
+        self.generate_subset_concrete_service(&mut subset, ServiceType::JobBoard,       properties.job_board_count,         rng);
 
         subset
     }
 
-    pub fn process_action(&mut self, id : ServiceId, action : ServiceAction, player : &mut Player, car : &mut Car) {
+    pub fn process_action(&mut self, id : ServiceId, action : ServiceAction, player : &mut Player, car : &mut Car, price_level : f32) -> Result<(), String> {
         match action {
-            ServiceAction::BuyGas(amount) => { self.get_service_mut::<GasStation>(id).buy_gas(amount, player); }
-            ServiceAction::RestInHostel(option_id) => { self.get_service_mut::<Hostel>(id).rest(option_id, player); }
-            ServiceAction::FixCarSystem(system, percent) => { self.get_service_mut::<RepairStation>(id).fix(system, percent, player, car); }
-            ServiceAction::BuyProduct(product_id) => { self.get_service_mut::<Shop>(id).buy_product(product_id, player); }
+            ServiceAction::BuyGas(amount) => { self.get_service_mut::<GasStation>(id).buy_gas(amount, player, price_level); }
+            ServiceAction::BuyOil(amount) => { self.get_service_mut::<GasStation>(id).buy_oil(amount, player, price_level); }
+            ServiceAction::RestInHostel(option_id) => { self.get_service_mut::<Hostel>(id).rest(option_id, player, price_level); }
+            ServiceAction::DiagnoseCar => { return self.get_service_mut::<RepairStation>(id).diagnose(player, price_level); }
+            ServiceAction::FixCarSystem(system, percent) => { self.get_service_mut::<RepairStation>(id).fix(system, percent, player, car, price_level); }
+            ServiceAction::BuyProduct(product_id) => { self.get_service_mut::<Shop>(id).buy_product(product_id, player, price_level); }
+            ServiceAction::TakeContract(contract_id, cargo) => { self.get_service_mut::<JobBoard>(id).take_contract(contract_id, cargo, player); }
         }
+
+        Ok(())
     }  
 
+    pub fn pass_time(&mut self, minutes : f32) {
+        for service in self.services.values_mut().flatten() {
+            service.pass_time(minutes);
+        }
+    }
+
+    pub fn save(&self, save : &mut SaveWriter) {
+        for (type_id, service_type) in ServiceType::all().iter().enumerate() {
+            for (id, service) in self.services.get(service_type).unwrap().iter().enumerate() {
+                let state = service.save_state();
+                if state.is_empty() { continue; }
+
+                let mut values = vec![type_id.to_string(), id.to_string()];
+                values.extend(state);
+                save.write("service", values);
+            }
+        }
+    }
+
+    pub fn load(&mut self, save : &SaveReader) -> Result<(), String> {
+        for entry in save.get_all("service") {
+            let service_type = *ServiceType::all().get(entry.get::<usize>(0)?).ok_or(String::from("incorrect service type"))?;
+            let service = self.services.get_mut(&service_type).unwrap()
+            .get_mut(entry.get::<usize>(1)?)
+            .ok_or(String::from("incorrect service id"))?;
+            service.load_state(&entry.values()[2..])?;
+        }
+
+        Ok(())
+    }
+
     fn get_service_mut<T>(&mut self, id : ServiceId) -> &mut T where T : Sized + 'static + Service {
         unsafe {
             &mut *(self.services.get_mut(&T::get_type()).unwrap()[id.0].as_mut() as *mut dyn Service as *mut T)
@@ -0,0 +1,57 @@
+use std::f32::consts::PI;
+
+use rand::{Rng, rngs::StdRng};
+
+use crate::engine::common::Math;
+use crate::game::save::parse_value;
+
+// Prices swing around the base price with the period of a few days.
+const DRIFT_AMPLITUDE : f32 = 0.15;
+const DRIFT_PERIOD_DAYS : (f32, f32) = (2.0, 6.0);
+// Demand raises prices and fades by e times during this time.
+const DEMAND_FADE_MINUTES : f32 = 24.0 * 60.0;
+const MAX_DEMAND : f32 = 1.0;
+
+#[derive(Clone)]
+pub struct Pricing {
+    drift_period : f32,
+    drift_phase : f32,
+    minutes : f32,
+    demand : f32
+}
+
+impl Pricing {
+    pub fn generate(rng : &mut StdRng) -> Pricing {
+        Pricing {
+            drift_period : rng.gen_range(DRIFT_PERIOD_DAYS.0, DRIFT_PERIOD_DAYS.1) * 24.0 * 60.0,
+            drift_phase : rng.gen_range(0.0, 1.0),
+            minutes : 0.0,
+            demand : 0.0
+        }
+    }
+
+    // Price level is the multiplier of the city the service is in.
+    pub fn get_price(&self, base_price : f32, price_level : f32) -> f32 {
+        let drift = 1.0 + DRIFT_AMPLITUDE * (2.0 * PI * (self.minutes / self.drift_period + self.drift_phase)).sin();
+        (base_price * price_level * drift * (1.0 + self.demand) * 100.0).round() / 100.0
+    }
+
+    pub fn add_demand(&mut self, demand : f32) {
+        self.demand = Math::min(self.demand + demand, MAX_DEMAND);
+    }
+
+    pub fn pass_time(&mut self, minutes : f32) {
+        self.minutes += minutes;
+        self.demand *= (-minutes / DEMAND_FADE_MINUTES).exp();
+    }
+
+    pub fn save_state(&self) -> Vec<String> {
+        vec![self.minutes.to_string(), self.demand.to_string()]
+    }
+
+    pub fn load_state(&mut self, state : &[String]) -> Result<(), String> {
+        self.minutes = parse_value(state, 0)?;
+        self.demand = parse_value(state, 1)?;
+        Ok(())
+    }
+}
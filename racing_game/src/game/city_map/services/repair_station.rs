@@ -5,42 +5,92 @@ use image::RgbaImage;
 
 use super::*;
 use crate::game::Percent;
-use crate::game::ride::car::*;
+use crate::engine::common::Math;
+
+// Ranges of costs to fix the whole car system.
+const REPAIR_COSTS : [(CarSystem, (f32, f32)); 6] = [
+    (CarSystem::Wheels, (20.0, 40.0)),
+    (CarSystem::Transmission, (40.0, 80.0)),
+    (CarSystem::Chase, (30.0, 60.0)),
+    (CarSystem::Engine, (60.0, 120.0)),
+    (CarSystem::Brake, (20.0, 40.0)),
+    (CarSystem::Starter, (15.0, 30.0))
+];
+const DIAGNOSIS_COST : (f32, f32) = (5.0, 15.0);
+// Demand added by every repaired percent.
+const REPAIR_DEMAND : f32 = 0.002;
 
 pub struct RepairStation {
-    logo : Rc<RgbaImage>,
+    brand : Brand,
     diagnosis_cost : f32,
-    pub repair_costs : HashMap<CarSystem, f32>
+    pub repair_costs : HashMap<CarSystem, f32>,
+    pricing : Pricing
 }   
 
 impl RepairStation {
-    pub fn generate(logo : RgbaImage, rng : &mut StdRng) -> RepairStation {
-        let diagnosis_cost = 10.0;
-        let mut repair_costs = HashMap::<CarSystem, f32>::new();
-        repair_costs.insert(CarSystem::Wheels, 5.0);
-        repair_costs.insert(CarSystem::Transmission, 5.0);
-        repair_costs.insert(CarSystem::Chase, 5.0);
-        repair_costs.insert(CarSystem::Engine, 5.0);
-        repair_costs.insert(CarSystem::Brake, 5.0);
-        repair_costs.insert(CarSystem::Starter, 5.0);
-
-        RepairStation { logo : Rc::from(logo), diagnosis_cost, repair_costs }
-    }
-
-    pub fn fix(&self, car_system : CarSystem, to_fix : Percent, player : &mut Player, car : &mut Car) {
-        let repair_cost = self.repair_costs.get(&car_system).unwrap() * to_fix.to_norm();
-        player.money -= repair_cost;
+    pub fn generate(brand : Brand, rng : &mut StdRng) -> RepairStation {
+        let diagnosis_cost = rng.gen_range(DIAGNOSIS_COST.0, DIAGNOSIS_COST.1).round();
+        let repair_costs : HashMap<CarSystem, f32> = REPAIR_COSTS.iter()
+        .map(|(system, (min_cost, max_cost))| (*system, rng.gen_range(min_cost, max_cost).round()))
+        .collect();
+
+        RepairStation { brand, diagnosis_cost, repair_costs, pricing : Pricing::generate(rng) }
+    }
+
+    // Cost is proportional to the repaired percentage.
+    pub fn get_repair_cost(&self, car_system : CarSystem, to_fix : Percent, price_level : f32) -> f32 {
+        self.pricing.get_price(self.repair_costs.get(&car_system).unwrap() * to_fix.to_norm(), price_level)
+    }
+
+    // System can't be fixed above 100 percents or for more money than the player has.
+    pub fn get_max_repair(&self, car_system : CarSystem, player : &Player, car : &Car, price_level : f32) -> Percent {
+        let damage = (100.0 - car.get_condition(car_system).0).ceil();
+        let affordable = (player.money / self.get_repair_cost(car_system, Percent(100.0), price_level) * 100.0).floor();
+        Percent(Math::max(Math::min(damage, affordable), 0.0))
+    }
+
+    pub fn fix(&mut self, car_system : CarSystem, to_fix : Percent, player : &mut Player, car : &mut Car, price_level : f32) {
+        let to_fix = Percent(Math::min(to_fix.0, self.get_max_repair(car_system, player, car, price_level).0));
+        player.money -= self.get_repair_cost(car_system, to_fix, price_level);
         car.fix_system(car_system, to_fix);
+        self.pricing.add_demand(to_fix.0 * REPAIR_DEMAND);
+    }
+
+    pub fn get_diagnosis_cost(&self, price_level : f32) -> f32 {
+        self.pricing.get_price(self.diagnosis_cost, price_level)
     }
 
-    pub fn get_diagnosis_cost(&self) -> f32 {
-        self.diagnosis_cost
+    pub fn diagnose(&self, player : &mut Player, price_level : f32) -> Result<(), String> {
+        let cost = self.get_diagnosis_cost(price_level);
+        if player.money < cost { return Err(format!("diagnosis costs ${}, you have ${}", cost, player.money.floor())); }
+        player.money -= cost;
+        Ok(())
     }
 }
 
 impl Service for RepairStation {
     fn get_logo(&self) -> Rc<RgbaImage> {
-        self.logo.clone()
+        self.brand.logo.clone()
+    }
+
+    fn get_name(&self) -> &str {
+        &self.brand.name
+    }
+
+    fn get_price_summary(&self, price_level : f32) -> String {
+        format!("DIAGNOSIS {}$", self.get_diagnosis_cost(price_level))
+    }
+
+    fn pass_time(&mut self, minutes : f32) {
+        self.pricing.pass_time(minutes);
+    }
+
+    fn save_state(&self) -> Vec<String> {
+        self.pricing.save_state()
+    }
+
+    fn load_state(&mut self, state : &[String]) -> Result<(), String> {
+        self.pricing.load_state(state)
     }
 
     fn get_ref_type(&self) -> ServiceType { ServiceType::RepairStation }
@@ -5,10 +5,78 @@ use image::RgbaImage;
 use super::*;
 use crate::game::Player;
 
+const PRODUCT_COUNT : (usize, usize) = (2, 6);
+// Save key, sizes and base cost of the product of size 1 for every kind of products.
+const PRODUCT_KINDS : [(&str, &[f32], (f32, f32)); 6] = [
+    ("water", &[0.5, 1.0, 1.5], (1.5, 3.0)),
+    ("soda", &[0.5, 1.0], (2.0, 4.0)),
+    ("food", &[0.5, 1.0, 1.5], (2.5, 5.0)),
+    ("parts", &[1.0, 2.0], (10.0, 20.0)),
+    ("jerrycan", &[5.0, 10.0], (7.0, 14.0)),
+    ("oil", &[1.0, 2.0], (4.0, 8.0))
+];
+// Demand added by every bought product.
+const PRODUCT_DEMAND : f32 = 0.05;
+
+// Products are carried in the inventory until they are used.
+#[derive(Clone, Copy)]
 pub enum ProductType {
     Water(f32),
     Soda(f32),
-    Food(f32)
+    Food(f32),
+    SpareParts(f32),
+    Jerrycan(f32),
+    Oil(f32)
+}
+
+impl ProductType {
+    pub fn from_save_key(key : &str, size : f32) -> Result<ProductType, String> {
+        match key {
+            "water" => { Ok(ProductType::Water(size)) }
+            "soda" => { Ok(ProductType::Soda(size)) }
+            "food" => { Ok(ProductType::Food(size)) }
+            "parts" => { Ok(ProductType::SpareParts(size)) }
+            "jerrycan" => { Ok(ProductType::Jerrycan(size)) }
+            "oil" => { Ok(ProductType::Oil(size)) }
+            _ => { Err(format!("unknown product [{}]", key)) }
+        }
+    }
+
+    pub fn get_save_key(&self) -> &'static str {
+        match self {
+            ProductType::Water(_) => { "water" }
+            ProductType::Soda(_) => { "soda" }
+            ProductType::Food(_) => { "food" }
+            ProductType::SpareParts(_) => { "parts" }
+            ProductType::Jerrycan(_) => { "jerrycan" }
+            ProductType::Oil(_) => { "oil" }
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ProductType::Water(_) => { "WATER" }
+            ProductType::Soda(_) => { "SODA" }
+            ProductType::Food(_) => { "FOOD" }
+            ProductType::SpareParts(_) => { "SPARE PARTS" }
+            ProductType::Jerrycan(_) => { "JERRYCAN" }
+            ProductType::Oil(_) => { "OIL" }
+        }
+    }
+
+    pub fn get_unit(&self) -> &'static str {
+        match self {
+            ProductType::Food(_) | ProductType::SpareParts(_) => { "KG." }
+            _ => { "L." }
+        }
+    }
+
+    pub fn get_size(&self) -> f32 {
+        match self {
+            ProductType::Water(size) | ProductType::Soda(size) | ProductType::Food(size) |
+            ProductType::SpareParts(size) | ProductType::Jerrycan(size) | ProductType::Oil(size) => { *size }
+        }
+    }
 }
 
 pub struct ShopProduct {
@@ -17,39 +85,67 @@ pub struct ShopProduct {
 }
 
 pub struct Shop {
-    logo : Rc<RgbaImage>,
-    pub assortment : Vec<ShopProduct>
+    brand : Brand,
+    pub assortment : Vec<ShopProduct>,
+    pricing : Pricing
 }
 
 impl Shop {
-    pub fn generate(logo : RgbaImage, rng : &mut StdRng) -> Shop {
-        let assortment = vec![
-            ShopProduct { product_type : ProductType::Water(0.5), cost : 1.0 },
-            ShopProduct { product_type : ProductType::Soda(1.0), cost : 2.0 },
-            ShopProduct { product_type : ProductType::Food(1.0), cost : 3.0 }
-        ];
+    pub fn generate(brand : Brand, rng : &mut StdRng) -> Shop {
+        let product_count = rng.gen_range(PRODUCT_COUNT.0, PRODUCT_COUNT.1);
+        let assortment = (0..product_count).map(|_| {
+            let (key, sizes, cost_per_size) = PRODUCT_KINDS[rng.gen_range(0, PRODUCT_KINDS.len())];
+            let size = sizes[rng.gen_range(0, sizes.len())];
+            let cost = (rng.gen_range(cost_per_size.0, cost_per_size.1) * size * 10.0).round() / 10.0;
+            ShopProduct { product_type : ProductType::from_save_key(key, size).unwrap(), cost }
+        })
+        .collect();
 
-        Shop { logo : Rc::from(logo), assortment }
+        Shop { brand, assortment, pricing : Pricing::generate(rng) }
     }
 
-    pub fn buy_product(&self, id : usize, player : &mut Player) {
-        let product = &self.assortment[id];
+    pub fn get_product_cost(&self, id : usize, price_level : f32) -> f32 {
+        self.pricing.get_price(self.assortment[id].cost, price_level)
+    }
 
-        match product.product_type {
-            ProductType::Water(size) => { player.thirst.sub(Percent(size * 20.0)); }
-            ProductType::Soda(size) => { player.thirst.sub(Percent(size * 10.0)); player.hunger.sub(Percent(size * 5.0)); }
-            ProductType::Food(size) => { player.hunger.sub(Percent(size * 20.0)); }
-        }
+    // Bought product goes to the inventory.
+    pub fn buy_product(&mut self, id : usize, player : &mut Player, price_level : f32) {
+        let cost = self.get_product_cost(id, price_level);
+        let product_type = self.assortment[id].product_type;
+        if player.money < cost || !player.inventory.fits(&product_type) { return; }
 
-        player.money -= product.cost;
+        player.money -= cost;
+        player.inventory.add(product_type);
+        self.pricing.add_demand(PRODUCT_DEMAND);
     }
 }
 
 impl Service for Shop {
     fn get_logo(&self) -> Rc<RgbaImage> {
-        self.logo.clone()
+        self.brand.logo.clone()
+    }
+
+    fn get_name(&self) -> &str {
+        &self.brand.name
+    }
+
+    fn get_price_summary(&self, price_level : f32) -> String {
+        let min_cost = (0..self.assortment.len()).map(|id| self.get_product_cost(id, price_level)).fold(f32::MAX, f32::min);
+        format!("FROM {}$", min_cost)
+    }
+
+    fn pass_time(&mut self, minutes : f32) {
+        self.pricing.pass_time(minutes);
+    }
+
+    fn save_state(&self) -> Vec<String> {
+        self.pricing.save_state()
+    }
+
+    fn load_state(&mut self, state : &[String]) -> Result<(), String> {
+        self.pricing.load_state(state)
     }
 
     fn get_ref_type(&self) -> ServiceType { ServiceType::Shop }
     fn get_type() -> ServiceType { ServiceType::Shop }
-}
\ No newline at end of file
+}
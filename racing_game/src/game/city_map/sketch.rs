@@ -0,0 +1,58 @@
+use rand::{Rng, rngs::StdRng};
+use image::{RgbaImage, Rgba};
+
+use crate::engine::common::Math;
+use crate::engine::billboards::BillboardFactory;
+use crate::game::SCREEN_RESOLUTION;
+
+// Billboard is as wide in road units as its image is in screens.
+const PIXELS_PER_UNIT : f32 = SCREEN_RESOLUTION.x as f32;
+// Generated sprites are drawn with big pixels, they are kept small and enlarged only when rendered.
+const SPRITE_PIXEL_SIZE : u32 = 8;
+
+// Sprite is drawn in coordinates relative to its size, from the left top corner.
+pub struct Sketch {
+    image : RgbaImage
+}
+
+impl Sketch {
+    // Same color is shifted by the same amount in every channel, so it only gets lighter or darker.
+    pub fn jitter_color(r : i32, g : i32, b : i32, jitter : i32, rng : &mut StdRng) -> Rgba<u8> {
+        let jitter = rng.gen_range(-jitter, jitter + 1);
+        let channel = |value : i32| Math::min(Math::max(value + jitter, 0), 255) as u8;
+        Rgba([channel(r), channel(g), channel(b), 255])
+    }
+
+    pub fn new(width : f32, height : f32) -> Sketch {
+        let size = |units : f32| Math::max((units * PIXELS_PER_UNIT / SPRITE_PIXEL_SIZE as f32).round() as u32, 1);
+        Sketch { image : RgbaImage::new(size(width), size(height)) }
+    }
+
+    fn fill(&mut self, color : Rgba<u8>, inside : impl Fn(f32, f32) -> bool) {
+        let (width, height) = (self.image.width() as f32, self.image.height() as f32);
+        for (x, y, pixel) in self.image.enumerate_pixels_mut() {
+            if inside((x as f32 + 0.5) / width, (y as f32 + 0.5) / height) { *pixel = color; }
+        }
+    }
+
+    pub fn fill_rect(&mut self, left : f32, top : f32, right : f32, bottom : f32, color : Rgba<u8>) {
+        self.fill(color, |x, y| x >= left && x <= right && y >= top && y <= bottom);
+    }
+
+    pub fn fill_ellipse(&mut self, center_x : f32, center_y : f32, radius_x : f32, radius_y : f32, color : Rgba<u8>) {
+        self.fill(color, |x, y| ((x - center_x) / radius_x).powi(2) + ((y - center_y) / radius_y).powi(2) <= 1.0);
+    }
+
+    // Triangle pointing up with the horizontal base.
+    pub fn fill_triangle(&mut self, top_x : f32, top_y : f32, base_left : f32, base_right : f32, base_y : f32, color : Rgba<u8>) {
+        self.fill(color, |x, y| {
+            if y < top_y || y > base_y { return false; }
+            let t = (y - top_y) / (base_y - top_y);
+            x >= Math::lerp(top_x, base_left, t) && x <= Math::lerp(top_x, base_right, t)
+        });
+    }
+
+    pub fn finish(self) -> BillboardFactory {
+        BillboardFactory::from_pixel_art(&self.image, SPRITE_PIXEL_SIZE)
+    }
+}
@@ -0,0 +1,73 @@
+use image::Rgb;
+
+use crate::engine::common::Math;
+use super::Time;
+use super::save::*;
+
+const MINUTES_IN_DAY : f32 = 24.0 * 60.0;
+const START_TIME : Time = Time { hr : 8, min : 0 };
+
+// Sky and light colors at hours of the day, light color multiplies the whole scene.
+const DAY_PHASES : [(f32, Rgb<u8>, Rgb<u8>); 8] = [
+    (0.0,  Rgb([10, 10, 40]),    Rgb([70, 70, 120])),
+    (5.0,  Rgb([10, 10, 40]),    Rgb([70, 70, 120])),
+    (7.0,  Rgb([250, 150, 100]), Rgb([230, 190, 170])),
+    (9.0,  Rgb([110, 170, 240]), Rgb([255, 255, 255])),
+    (17.0, Rgb([110, 170, 240]), Rgb([255, 255, 255])),
+    (19.0, Rgb([240, 120, 80]),  Rgb([230, 170, 150])),
+    (21.0, Rgb([10, 10, 40]),    Rgb([70, 70, 120])),
+    (24.0, Rgb([10, 10, 40]),    Rgb([70, 70, 120]))
+];
+
+// World time in minutes since the start of the journey.
+#[derive(Clone)]
+pub struct Clock {
+    minutes : f32
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock { minutes : START_TIME.to_minutes() as f32 }
+    }
+
+    pub fn advance(&mut self, minutes : f32) {
+        self.minutes += minutes;
+    }
+
+    pub fn get_minutes(&self) -> f32 {
+        self.minutes
+    }
+
+    pub fn get_day(&self) -> u32 {
+        (self.minutes / MINUTES_IN_DAY) as u32 + 1
+    }
+
+    pub fn get_time_of_day(&self) -> Time {
+        let minutes = (self.minutes % MINUTES_IN_DAY) as u32;
+        Time::new(minutes / 60, minutes % 60)
+    }
+
+    // Sky color and light color.
+    pub fn get_lighting(&self) -> (Rgb<u8>, Rgb<u8>) {
+        let hour = (self.minutes % MINUTES_IN_DAY) / 60.0;
+        let next_phase = DAY_PHASES.iter().position(|(phase_hour, _, _)| *phase_hour > hour).unwrap_or(DAY_PHASES.len() - 1);
+        let (start_hour, start_sky, start_light) = DAY_PHASES[next_phase - 1];
+        let (end_hour, end_sky, end_light) = DAY_PHASES[next_phase];
+        let t = (hour - start_hour) / (end_hour - start_hour);
+
+        let lerp = |a : Rgb<u8>, b : Rgb<u8>| Rgb([
+            Math::lerp(a[0] as f32, b[0] as f32, t) as u8,
+            Math::lerp(a[1] as f32, b[1] as f32, t) as u8,
+            Math::lerp(a[2] as f32, b[2] as f32, t) as u8
+        ]);
+        (lerp(start_sky, end_sky), lerp(start_light, end_light))
+    }
+
+    pub fn save(&self, save : &mut SaveWriter) {
+        save.write("clock", vec![self.minutes.to_string()]);
+    }
+
+    pub fn load(save : &SaveReader) -> Result<Clock, String> {
+        Ok(Clock { minutes : save.get("clock")?.get(0)? })
+    }
+}
@@ -0,0 +1,53 @@
+use crate::game::city_map::services::ProductType;
+use crate::game::save::*;
+
+// Total size of carried products, liters and kilograms are counted the same.
+const INVENTORY_CAPACITY : f32 = 20.0;
+
+#[derive(Clone)]
+pub struct Inventory {
+    pub items : Vec<ProductType>,
+    pub capacity : f32
+}
+
+impl Inventory {
+    pub fn new() -> Inventory {
+        Inventory { items : Vec::new(), capacity : INVENTORY_CAPACITY }
+    }
+
+    pub fn get_load(&self) -> f32 {
+        self.items.iter().fold(0.0, |load, item| load + item.get_size())
+    }
+
+    pub fn fits(&self, item : &ProductType) -> bool {
+        self.get_load() + item.get_size() <= self.capacity
+    }
+
+    pub fn add(&mut self, item : ProductType) {
+        if self.fits(&item) { self.items.push(item); }
+    }
+
+    pub fn find_jerrycan(&self) -> Option<usize> {
+        self.items.iter().position(|item| matches!(item, ProductType::Jerrycan(_)))
+    }
+
+    pub fn take(&mut self, id : usize) -> Option<ProductType> {
+        if id < self.items.len() { Some(self.items.remove(id)) } else { None }
+    }
+
+    pub fn save(&self, save : &mut SaveWriter) {
+        for item in &self.items {
+            save.write("player_item", vec![item.get_save_key().to_string(), item.get_size().to_string()]);
+        }
+    }
+
+    pub fn load(save : &SaveReader) -> Result<Inventory, String> {
+        let mut inventory = Inventory::new();
+        for entry in save.get_all("player_item") {
+            let key : String = entry.get(0)?;
+            inventory.items.push(ProductType::from_save_key(&key, entry.get(1)?)?);
+        }
+
+        Ok(inventory)
+    }
+}
@@ -1,45 +1,66 @@
-use std::rc::Rc;
+use std::cell::RefCell;
 use std::iter;
+use std::path::Path;
 
 extern crate include_dir;
 extern crate rand;
 extern crate readonly;
 
-use rand::*;
 use crate::image::{RgbImage, RgbaImage};
 use include_dir::{include_dir, Dir};
 
 use crate::engine::input::*;
-use crate::engine::render::*;
+use crate::engine::backend::*;
 use crate::engine::window::*;
-use crate::engine::common::{IVec2};
+use crate::engine::common::{IVec2, Math};
 
 mod city_map;
 use city_map::*;
 
 mod ride;
 use ride::*;
+use ride::car::Car;
+use ride::car_model::*;
 
 mod player;
 use player::*;
 
+mod inventory;
+
+mod clock;
+use clock::*;
+
 mod ui;
 use ui::*;
 
+mod save;
+use save::*;
+
+pub mod new_game;
+use new_game::*;
+
 pub mod key_name;
 
 pub const RESOURCES_DIR : Dir = include_dir!("./resources");
 pub const SCREEN_RESOLUTION : IVec2 = IVec2 { x : 640, y : 360 };
 
+const HOSPITAL_COST : f32 = 50.0;
+const HOSPITAL_MINUTES : f32 = 12.0 * 60.0;
+pub const SAVE_FILE : &str = "save.txt";
+
 pub struct Game {
-    window : Window,
-    render : Render,
+    backend : Box<dyn Backend>,
     pub input : Input<InputEvent>,
 
+    seed : WorldSeed,
+
     player : Player,
+    clock : Clock,
+    car_models : Vec<CarModel>,
 
     pub city_map : CityMap,
-    ui : UI,
+    // Screens read the game while they are updated, so the UI is borrowed apart from it.
+    ui : RefCell<UI>,
 
     pub ride : Ride 
 }
@@ -77,13 +98,11 @@ pub struct Time { pub hr : u32, pub min : u32 }
 
 impl Time {
     pub fn new(hr : u32, min : u32) -> Time { Time { hr, min } }
+    pub fn to_minutes(&self) -> u32 { self.hr * 60 + self.min }
 }
 
 impl Game {
-    pub fn new() -> Game {
-        let window = Window::open(WindowParameters { width : SCREEN_RESOLUTION.x as u32, height : SCREEN_RESOLUTION.y as u32, title : String::from("title")});
-        let render = Render::new(SCREEN_RESOLUTION.x as u32, SCREEN_RESOLUTION.y as u32);
-
+    pub fn new(backend : Box<dyn Backend>, parameters : NewGameParameters) -> Game {
         let mut input = Input::<InputEvent>::new();
         input.bind_action(InputEvent::CarGas, Key::Up);
         input.bind_action(InputEvent::CarLeft, Key::Left);
@@ -98,60 +117,61 @@ impl Game {
         input.bind_action(InputEvent::UIBack, Key::Backspace);
         input.bind_action(InputEvent::UIMenu, Key::Escape);
 
-        let mut generation_rng = rand::rngs::StdRng::from_seed([10, 2, 3, 4, 5, 6, 7, 8, 9, 1, 2, 3, 4, 5, 6, 7, 8, 9, 1, 2, 3, 4, 5, 6, 7, 8, 9, 1, 2, 3, 4, 5]);
-        let parameters = city_map::GenerationParameters { 
-            city_count : 19, 
-            size : IVec2::new(300, 300),
-            min_distance_between_cities : 50.0,
-            road_length_multiplier : 2.0
-        };
-        let city_map = CityMap::generate(&mut generation_rng, parameters);
+        let seed = parameters.seed;
+        let city_map = CityMap::generate(&mut seed.rng(), parameters.generation);
         
-        let ride = Ride::new();
-
-        let ui = UI::new(&SCREEN_RESOLUTION);
-
         let player = Player::new();
+        let car_models = CarModel::load_all();
+        let car_model = car_models.iter().find(|model| model.id == player.car_model).expect("default car model not found!");
+        
+        let ride = Ride::new(car_model);
 
-        Game { window, render, input, city_map, ride, ui, player }
+        let ui = RefCell::new(UI::new(&SCREEN_RESOLUTION));
+
+        Game { backend, input, seed, city_map, ride, ui, player, clock : Clock::new(), car_models }
     }
 }
 
 // Game loop.
 impl Game {
-    pub fn enter_gameloop(&mut self) {
-        unsafe {
-            self.ui.set_game(Rc::from_raw(self as *const Game));
-        }
+    // Stops when the backend asks to close or can't present a frame.
+    pub fn enter_gameloop(&mut self) -> Result<(), String> {
+        self.ui.borrow_mut().init(self);
 
         loop {
-            let delta_time = self.window.get_time();
-            self.window.set_time(0.0);
+            let delta_time = self.backend.get_time();
+            self.backend.set_time(0.0);
             
             //println!("FPS : {}", 1.0 / delta_time);
             
             self.update(delta_time as f32);
 
             let render_buffer = RgbImage::new(SCREEN_RESOLUTION.x as u32, SCREEN_RESOLUTION.y as u32);
-            if self.window.should_close() { break; }
-            self.render(render_buffer);
+            if self.backend.should_close() { return Ok(()); }
+            self.render(render_buffer)?;
         }
     }
 
     fn update(&mut self, delta_time : f32) {
-        let key_events = self.window.get_events();
+        let key_events = self.backend.get_events();
         let mut input_queue : Vec<(InputEvent, EventType)> = Vec::with_capacity(key_events.len());
         for (key, event_type) in &key_events {
             input_queue.push((InputEvent::AnyKey(*key), *event_type));
         }
         input_queue.append(&mut self.input.process(key_events));
 
-        let ui_events = self.ui.update(&input_queue, delta_time);
+        let ui_events = self.ui.borrow_mut().update(self, &input_queue, delta_time);
 
         for event in ui_events {
             match event {
                 UIEvent::StartRide => { 
-                    self.ride.start_ride(self.city_map.get_current_road_meta(), self.player.clone()); 
+                    match self.city_map.get_current_road_meta() {
+                        Ok(road_meta) => { self.ride.start_ride(road_meta, self.player.clone()); }
+                        Err(e) => {
+                            self.ui.borrow_mut().enter_city(self);
+                            self.ui.borrow_mut().show_message(&format!("can't start ride : {}", e));
+                        }
+                    }
                 }
                 UIEvent::SelectCityDestination(destination) => {
                     self.city_map.set_city_destination(destination);
@@ -160,7 +180,16 @@ impl Game {
                     self.player = player;
                 }
                 UIEvent::ServiceAction(id, action) => {
-                    self.city_map.process_service_action(id, action, &mut self.player, &mut self.ride.car); 
+                    if let services::ServiceAction::RestInHostel(option_id) = &action {
+                        let hostel = self.city_map.get_service::<services::Hostel>(id);
+                        // Time passes only if the room is paid.
+                        if self.player.money >= hostel.get_rest_cost(*option_id, self.city_map.get_price_level()) {
+                            self.pass_time(hostel.get_rest_minutes(*option_id));
+                        }
+                    }
+                    if let Err(e) = self.city_map.process_service_action(id, action, &mut self.player, &mut self.ride.car) {
+                        self.ui.borrow_mut().show_message(&e);
+                    }
                 }
                 UIEvent::SetRidePaused(paused) => {
                     self.ride.set_paused(paused);
@@ -170,6 +199,38 @@ impl Game {
                 UIEvent::BindKey(action, key) => { 
                     self.input.override_action_binding(action, key); 
                 }
+                UIEvent::NewGame(parameters) => {
+                    self.start_new_game(parameters);
+                    self.ui.borrow_mut().enter_city(self);
+                }
+                UIEvent::UseItem(item_id) => {
+                    self.player.use_item(item_id, &mut self.ride.car);
+                    self.ride.set_player(self.player.clone());
+                }
+                UIEvent::SelectCar(model_id) => {
+                    match self.select_car(&model_id) {
+                        Ok(_) => { self.ui.borrow_mut().init(self); }
+                        Err(e) => { self.ui.borrow_mut().show_message(&e); }
+                    }
+                }
+                UIEvent::Rescue(option) => {
+                    match self.rescue(option) {
+                        Ok(_) => { }
+                        Err(e) => { self.ui.borrow_mut().show_message(&e); }
+                    }
+                }
+                UIEvent::SaveGame => {
+                    match self.save_game(Path::new(SAVE_FILE)) {
+                        Ok(_) => { self.ui.borrow_mut().show_message(&format!("game saved to {}", SAVE_FILE)); }
+                        Err(e) => { self.ui.borrow_mut().show_message(&format!("can't save game : {}", e)); }
+                    }
+                }
+                UIEvent::LoadGame => {
+                    match self.load_game(Path::new(SAVE_FILE)) {
+                        Ok(_) => { if self.ride.is_active() { self.ui.borrow_mut().enter_ride(self); } else { self.ui.borrow_mut().enter_city(self); } }
+                        Err(e) => { self.ui.borrow_mut().show_message(&format!("can't load game : {}", e)); }
+                    }
+                }
             } 
         }
 
@@ -178,23 +239,243 @@ impl Game {
         for event in ride_events {
             match event {
                 RideEvent::Finished => { 
-                    self.city_map.arrived_to_city();
-                    self.ui.enter_city(); 
+                    self.player.money += self.ride.get_arrival_reward();
+                    self.arrive_to_city();
+                    if self.city_map.is_in_finish_city() { self.ui.borrow_mut().enter_victory(self); } else { self.ui.borrow_mut().enter_city(self); }
                 }
                 RideEvent::ChangePlayer(player) => {
                     self.player = player;
                 }
+                RideEvent::TimePassed(minutes) => {
+                    self.pass_time(minutes);
+                }
+                RideEvent::Collapsed => {
+                    self.collapse();
+                }
+                RideEvent::EngineSeized => {
+                    // Seized engine can only be towed.
+                    if self.player.money >= self.get_tow_cost() {
+                        self.ride.set_paused(true);
+                        self.ui.borrow_mut().enter_rescue(self);
+                    } else {
+                        self.ride.stop();
+                        self.ui.borrow_mut().enter_game_over(self);
+                    }
+                }
+                RideEvent::OutOfGas => {
+                    if self.can_be_rescued() {
+                        self.ride.set_paused(true);
+                        self.ui.borrow_mut().enter_rescue(self);
+                    } else {
+                        self.ride.stop();
+                        self.ui.borrow_mut().enter_game_over(self);
+                    }
+                }
             } 
         }
 
         self.ride.process_input(&input_queue);
+
+        let (sky_color, light_color) = self.clock.get_lighting();
+        self.ride.set_lighting(sky_color, light_color);
     }
 
-    fn render(&mut self, mut buffer : RgbImage) {
+    fn render(&mut self, mut buffer : RgbImage) -> Result<(), String> {
         self.ride.render(&mut buffer);
-        self.ui.render(&mut buffer);
+        self.ui.borrow_mut().render(&mut buffer);
+
+        self.backend.present(buffer)
+    }
+}
+
+// Help on the road.
+impl Game {
+    // Cargo for this city is unloaded however the player got here.
+    fn arrive_to_city(&mut self) {
+        self.city_map.arrived_to_city();
+
+        let now = self.clock.get_minutes();
+        let current_city_id = self.city_map.current_city_id;
+        let (delivered, cargo) : (Vec<_>, Vec<_>) = self.player.cargo.drain(..).partition(|cargo| cargo.destination_city_id == current_city_id);
+        self.player.cargo = cargo;
+        self.player.money += delivered.iter().map(|cargo| cargo.get_payment(now)).sum::<f32>();
+    }
+
+    pub fn get_clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn get_tow_cost(&self) -> f32 {
+        self.ride.get_tow_cost()
+    }
+
+    // Prices in all the cities change with time.
+    fn pass_time(&mut self, minutes : f32) {
+        self.clock.advance(minutes);
+        self.city_map.pass_time(minutes);
+    }
+
+    // Player who can't pay for any help on the road and carries no gas loses.
+    fn can_be_rescued(&self) -> bool {
+        self.player.money >= Math::min(self.get_tow_cost(), RESCUE_GAS_COST) || self.player.inventory.find_jerrycan().is_some()
+    }
+
+    // Collapsed player wakes up in the hospital of the destination city.
+    fn collapse(&mut self) {
+        self.player.money = Math::max(self.player.money - HOSPITAL_COST, 0.0);
+        self.player.recover();
+        self.pass_time(HOSPITAL_MINUTES);
+        self.arrive_to_city();
+        self.ui.borrow_mut().enter_city(self);
+    }
+
+    fn rescue(&mut self, option : RescueOption) -> Result<(), String> {
+        match option {
+            RescueOption::Tow => {
+                let tow_cost = self.get_tow_cost();
+                if self.player.money < tow_cost { return Err(format!("tow costs ${}, you have ${}", tow_cost.ceil(), self.player.money.floor())); }
+                self.player.money -= tow_cost;
+                self.ride.stop();
+                self.arrive_to_city();
+                self.ui.borrow_mut().enter_city(self);
+            }
+            RescueOption::Refuel => {
+                if self.player.money < RESCUE_GAS_COST { return Err(format!("gas costs ${}, you have ${}", RESCUE_GAS_COST, self.player.money.floor())); }
+                self.player.money -= RESCUE_GAS_COST;
+                self.player.gas_level = Math::min(self.player.gas_level + RESCUE_GAS_AMOUNT, self.player.max_gas_level);
+                self.ride.set_player(self.player.clone());
+                self.ride.set_paused(false);
+                self.ui.borrow_mut().enter_ride(self);
+            }
+            RescueOption::UseJerrycan => {
+                let item_id = self.player.inventory.find_jerrycan().ok_or(String::from("there is no jerrycan in the inventory"))?;
+                self.player.use_item(item_id, &mut self.ride.car);
+                self.ride.set_player(self.player.clone());
+                self.ride.set_paused(false);
+                self.ui.borrow_mut().enter_ride(self);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Garage.
+impl Game {
+    pub fn get_car_models(&self) -> &Vec<CarModel> {
+        &self.car_models
+    }
+
+    pub fn get_player_car_model(&self) -> &str {
+        &self.player.car_model
+    }
+
+    fn find_car_model(&self, model_id : &str) -> Result<&CarModel, String> {
+        self.car_models.iter().find(|model| model.id == model_id).ok_or(format!("unknown car model [{}]", model_id))
+    }
+
+    // Old car is left in the garage, so taking an owned model is free.
+    fn select_car(&mut self, model_id : &str) -> Result<(), String> {
+        if !self.city_map.is_in_start_city() { return Err(String::from("garage is only in the start city")); }
+        if self.player.car_model == model_id { return Ok(()); }
 
-        self.render.render(&mut self.window, buffer);
+        let model = self.find_car_model(model_id)?.clone();
+        if !self.player.owned_car_models.contains(&model.id) {
+            if self.player.money < model.price { return Err(format!("{} costs ${}, you have ${}", model.name, model.price, self.player.money.floor())); }
+            self.player.money -= model.price;
+            self.player.owned_car_models.push(model.id.clone());
+        }
+
+        self.player.car_model = model.id.clone();
+        self.ride.set_car_model(&model);
+
+        Ok(())
+    }
+}
+
+// Save and load.
+impl Game {
+    pub fn get_new_game_parameters(&self) -> NewGameParameters {
+        NewGameParameters { seed : self.seed, generation : self.city_map.parameters.clone() }
+    }
+
+    fn start_new_game(&mut self, parameters : NewGameParameters) {
+        self.city_map = CityMap::generate(&mut parameters.seed.rng(), parameters.generation);
+        self.seed = parameters.seed;
+        self.player = Player::new();
+        self.clock = Clock::new();
+        self.ride.stop();
+        let car_model = self.find_car_model(&self.player.car_model).unwrap().clone();
+        self.ride.set_car_model(&car_model);
+    }
+
+    // Map isn't stored, it is regenerated from the seed and then saved progress is applied to it.
+    fn save_game(&self, path : &Path) -> Result<(), String> {
+        let mut save = SaveWriter::new();
+
+        save.write("seed", vec![self.seed.to_code()]);
+        let parameters = &self.city_map.parameters;
+        save.write("map_parameters", vec![
+            parameters.city_count.to_string(),
+            parameters.size.x.to_string(),
+            parameters.size.y.to_string(),
+            parameters.min_distance_between_cities.to_string(),
+            parameters.road_length_multiplier.to_string(),
+            parameters.service_count.to_string()
+        ]);
+
+        self.city_map.save(&mut save);
+        self.player.save(&mut save);
+        self.clock.save(&mut save);
+        self.ride.car.save(&mut save);
+        self.ride.save(&mut save);
+
+        save.save(path)
+    }
+
+    fn load_game(&mut self, path : &Path) -> Result<(), String> {
+        let save = SaveReader::load(path)?;
+
+        let seed = WorldSeed::from_code(&save.get("seed")?.get::<String>(0)?)?;
+
+        let parameters_entry = save.get("map_parameters")?;
+        let parameters = city_map::GenerationParameters {
+            city_count : parameters_entry.get(0)?,
+            size : IVec2::new(parameters_entry.get(1)?, parameters_entry.get(2)?),
+            min_distance_between_cities : parameters_entry.get(3)?,
+            road_length_multiplier : parameters_entry.get(4)?,
+            service_count : parameters_entry.get(5)?
+        };
+        // Edited save could ask for a map that can't be generated.
+        NewGameParameters { seed, generation : parameters.clone() }.validate()?;
+
+        let mut city_map = CityMap::generate(&mut seed.rng(), parameters);
+        city_map.load(&save)?;
+        let player = Player::load(&save)?;
+        let clock = Clock::load(&save)?;
+        let ride = match save.get("ride") {
+            Ok(entry) => { Some((city_map.get_current_road_meta()?, entry.get::<f32>(0)?, entry.get::<f32>(1)?, entry.get::<f32>(2)?)) }
+            Err(_) => { None }
+        };
+        let mut car = Car::from_model(self.find_car_model(&player.car_model)?);
+        car.load(&save)?;
+
+        // Everything is parsed, so the game can't end up half-loaded.
+        self.seed = seed;
+        self.city_map = city_map;
+        self.player = player;
+        self.clock = clock;
+        self.ride.car = car;
+
+        match ride {
+            Some((road_meta, road_distance, x_pos, ride_minutes)) => {
+                self.ride.start_ride(road_meta, self.player.clone());
+                self.ride.set_progress(road_distance, x_pos, ride_minutes);
+            }
+            None => { self.ride.stop(); }
+        }
+
+        Ok(())
     }
 }
 
@@ -209,18 +490,156 @@ impl Game {
     }
 
     pub fn load_image_rgba(name : &str) -> RgbaImage {
-        let file = RESOURCES_DIR.get_file(name);
-        match file {
-            Some(file) => { image::load_from_memory(file.contents()).unwrap().to_rgba() } 
+        match Self::find_image_rgba(name) {
+            Some(image) => { image } 
             None => { panic!("file {} not found!", name); }
         }
     }
 
-    pub fn load_file<'a>(name : &str) -> &'a [u8] { 
-        let file = RESOURCES_DIR.get_file(name);
-        match file {
-            Some(file) => { file.contents() } 
-            None => { panic!("file {} not found!", name); }
+    // For optional resources that are generated when missing.
+    pub fn find_image_rgba(name : &str) -> Option<RgbaImage> {
+        RESOURCES_DIR.get_file(name).map(|file| image::load_from_memory(file.contents()).unwrap().to_rgba())
+    }
+
+    pub fn load_font() -> crate::engine::ui::font::Font {
+        crate::engine::ui::font::Font::new(Self::load_image_rgba("font.png"), IVec2::new(12, 12), String::from("ABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890$%., "))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::engine::replay::*;
+    use city_map::services::*;
+    use new_game::{NewGameParameters, WorldSeed};
+
+    use super::*;
+
+    const DELTA_TIME : f64 = 1.0 / 60.0;
+    const GOLDEN_DIR : &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+    // Keeps the last presented frame, so it isn't saved to disk and read back on every frame.
+    struct CaptureBackend {
+        inner : Box<dyn Backend>,
+        last_frame : Rc<RefCell<Option<RgbImage>>>
+    }
+
+    impl Backend for CaptureBackend {
+        fn get_events(&mut self) -> Vec<(Key, EventType)> { self.inner.get_events() }
+        fn should_close(&self) -> bool { self.inner.should_close() }
+        fn get_time(&self) -> f64 { self.inner.get_time() }
+        fn set_time(&mut self, time : f64) { self.inner.set_time(time); }
+
+        fn present(&mut self, image : RgbImage) -> Result<(), String> {
+            *self.last_frame.borrow_mut() = Some(image.clone());
+            self.inner.present(image)
         }
     }
-}
\ No newline at end of file
+
+    fn tap(key : Key) -> Vec<RecordedFrame> {
+        vec![
+            RecordedFrame { delta_time : DELTA_TIME, events : vec![(key, EventType::Pressed)] },
+            RecordedFrame { delta_time : DELTA_TIME, events : vec![(key, EventType::Released)] }
+        ]
+    }
+
+    fn hold(key : Key, frame_count : usize) -> Vec<RecordedFrame> {
+        let mut frames = vec![RecordedFrame { delta_time : DELTA_TIME, events : vec![(key, EventType::Pressed)] }];
+        frames.extend(iter::repeat_n(RecordedFrame { delta_time : DELTA_TIME, events : Vec::new() }, frame_count));
+        frames.push(RecordedFrame { delta_time : DELTA_TIME, events : vec![(key, EventType::Released)] });
+        frames
+    }
+
+    // Replays the frames headlessly, the last one is flipped the same way the headless backend saves frames.
+    fn render_last_frame(seed : &str, frames : Vec<RecordedFrame>) -> RgbImage {
+        let last_frame = Rc::new(RefCell::new(None));
+        let record = InputRecord { properties : Vec::new(), frames };
+        let headless = Box::from(HeadlessBackend::new(u32::MAX, DELTA_TIME, None).unwrap());
+        let backend = Box::from(CaptureBackend { inner : Box::from(ReplayBackend::new(record, headless)), last_frame : last_frame.clone() });
+
+        let parameters = NewGameParameters::new(WorldSeed::from_code(seed).unwrap());
+        Game::new(backend, parameters).enter_gameloop().unwrap();
+
+        let frame = last_frame.borrow_mut().take().expect("no frame was presented!");
+        image::imageops::flip_vertical(&frame)
+    }
+
+    // Starts a new game, picks the first destination and drives for a bit, so the road,
+    // scenery, traffic, HUD and font all end up in the frame.
+    #[test]
+    fn headless_ride_frame_matches_golden_image() {
+        let mut frames = Vec::new();
+        for _ in 0..4 { frames.extend(tap(Key::Down)); }
+        frames.extend(tap(Key::Enter));
+        for _ in 0..9 { frames.extend(tap(Key::Down)); }
+        frames.extend(tap(Key::Enter));
+        frames.extend(tap(Key::Enter));
+        frames.extend(hold(Key::Up, 20));
+
+        let frame = render_last_frame("0", frames);
+
+        let golden_path = Path::new(GOLDEN_DIR).join("ride.png");
+        let golden = image::open(&golden_path).unwrap_or_else(|e| panic!("can't open {} : {}", golden_path.display(), e)).to_rgb();
+        assert!(frame == golden, "frame differs from {}", golden_path.display());
+    }
+
+    fn new_test_game(seed : &str) -> Game {
+        let backend = Box::from(HeadlessBackend::new(0, DELTA_TIME, None).unwrap());
+        Game::new(backend, NewGameParameters::new(WorldSeed::from_code(seed).unwrap()))
+    }
+
+    // Everything saved is restored, so saving the loaded game gives the same file.
+    #[test]
+    fn saved_game_loads_back_unchanged() {
+        let save_dir = std::env::temp_dir().join(format!("racing_game_save_{}", std::process::id()));
+        std::fs::create_dir_all(&save_dir).unwrap();
+        let (saved_path, resaved_path) = (save_dir.join("saved.txt"), save_dir.join("resaved.txt"));
+
+        // Player, car, services and ride differ from the ones of a new game.
+        let mut game = new_test_game("SAVE");
+        game.player.money = 1000.0;
+        let shop_id = game.city_map.get_current_city_services_subset().get_of_type::<Shop>()[0];
+        game.city_map.process_service_action(shop_id, ServiceAction::BuyProduct(0), &mut game.player, &mut game.ride.car).unwrap();
+        game.player.cargo.push(Cargo { destination_city_id : 1, reward : 120.5, deadline : 900.0, fragility : 0.3, condition : Percent(75.0) });
+        game.pass_time(300.0);
+        game.ride.car.speed = 10.0;
+        game.ride.car.collide();
+        game.city_map.set_city_destination(game.city_map.get_accesible_city_ids()[0]);
+        game.ride.start_ride(game.city_map.get_current_road_meta().unwrap(), game.player.clone());
+        game.ride.set_progress(120.5, 0.25, 42.0);
+        game.save_game(&saved_path).unwrap();
+
+        let mut loaded = new_test_game("LOAD");
+        loaded.load_game(&saved_path).unwrap();
+        loaded.save_game(&resaved_path).unwrap();
+
+        let saved = std::fs::read_to_string(&saved_path).unwrap();
+        let resaved = std::fs::read_to_string(&resaved_path).unwrap();
+        std::fs::remove_dir_all(&save_dir).unwrap();
+        assert!(saved.contains("player_item"));
+        assert!(loaded.ride.is_active());
+        assert_eq!(saved, resaved);
+    }
+
+    #[test]
+    fn ride_save_without_road_isnt_loaded() {
+        let save_path = std::env::temp_dir().join(format!("racing_game_road_save_{}.txt", std::process::id()));
+
+        let mut game = new_test_game("SAVE");
+        game.city_map.set_city_destination(game.city_map.get_accesible_city_ids()[0]);
+        game.ride.start_ride(game.city_map.get_current_road_meta().unwrap(), game.player.clone());
+        game.save_game(&save_path).unwrap();
+        // There is no road from the city to itself.
+        let saved = std::fs::read_to_string(&save_path).unwrap();
+        let destination_line = saved.lines().find(|line| line.starts_with("destination_city")).unwrap();
+        let edited = saved.replace(destination_line, &format!("destination_city {}", game.city_map.current_city_id));
+        std::fs::write(&save_path, edited).unwrap();
+
+        let mut loaded = new_test_game("LOAD");
+        let result = loaded.load_game(&save_path);
+        std::fs::remove_file(&save_path).unwrap();
+        assert!(result.is_err());
+        assert!(!loaded.ride.is_active());
+    }
+}
+
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::Path;
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::engine::common::IVec2;
+use super::city_map::GenerationParameters;
+
+const SEED_CODE_DIGITS : &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub const MAX_SEED_CODE_LENGTH : usize = 12;
+
+// Seed is shared between players as a short base 36 code, so it can be typed with the game font.
+#[derive(Clone, Copy, PartialEq)]
+pub struct WorldSeed(pub u64);
+
+impl WorldSeed {
+    pub fn random() -> WorldSeed {
+        WorldSeed::random_from(&mut rand::thread_rng())
+    }
+
+    pub fn random_from<R>(rng : &mut R) -> WorldSeed where R : Rng {
+        // Keep generated codes up to 8 symbols long.
+        WorldSeed(rng.gen_range(0, 36u64.pow(8)))
+    }
+
+    pub fn from_code(code : &str) -> Result<WorldSeed, String> {
+        if code.is_empty() || code.len() > MAX_SEED_CODE_LENGTH {
+            return Err(format!("seed [{}] should be 1 to {} symbols long", code, MAX_SEED_CODE_LENGTH));
+        }
+
+        let mut seed = 0u64;
+        for symbol in code.to_uppercase().bytes() {
+            let digit = SEED_CODE_DIGITS.iter().position(|&d| d == symbol).ok_or(format!("incorrect symbol in seed [{}]", code))?;
+            seed = seed * SEED_CODE_DIGITS.len() as u64 + digit as u64;
+        }
+
+        Ok(WorldSeed(seed))
+    }
+
+    pub fn to_code(self) -> String {
+        let base = SEED_CODE_DIGITS.len() as u64;
+        let mut seed = self.0;
+        let mut code = Vec::new();
+        loop {
+            code.push(SEED_CODE_DIGITS[(seed % base) as usize]);
+            seed /= base;
+            if seed == 0 { break; }
+        }
+        code.reverse();
+
+        String::from_utf8(code).unwrap()
+    }
+
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.0)
+    }
+}
+
+#[derive(Clone)]
+pub struct NewGameParameters {
+    pub seed : WorldSeed,
+    pub generation : GenerationParameters
+}
+
+impl NewGameParameters {
+    pub const MIN_MAP_SIZE : isize = 200;
+    pub const MAX_MAP_SIZE : isize = 300;
+    pub const MIN_CITY_COUNT : u32 = 5;
+    pub const DEFAULT_SERVICE_COUNT : u32 = 7;
+    // Every city has up to this many services of one type, all of them different.
+    pub const MIN_SERVICE_COUNT : u32 = 3;
+    // Services of one type are listed on one screen.
+    pub const MAX_SERVICE_COUNT : u32 = 15;
+
+    pub fn new(seed : WorldSeed) -> NewGameParameters {
+        NewGameParameters {
+            seed,
+            generation : GenerationParameters {
+                city_count : 19,
+                size : IVec2::new(300, 300),
+                min_distance_between_cities : 50.0,
+                road_length_multiplier : 2.0,
+                service_count : Self::DEFAULT_SERVICE_COUNT
+            }
+        }
+    }
+
+    // Cities are placed randomly with minimal distance between them, so only this many fit on the map for sure.
+    pub fn max_city_count(&self) -> u32 {
+        let min_distance = self.generation.min_distance_between_cities;
+        let size = self.generation.size.x as f32;
+        (size * size / (min_distance * min_distance * 1.6)) as u32
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let size = self.generation.size;
+        if size.x != size.y || size.x < Self::MIN_MAP_SIZE || size.x > Self::MAX_MAP_SIZE {
+            return Err(format!("map should be square with size from {} to {}", Self::MIN_MAP_SIZE, Self::MAX_MAP_SIZE));
+        }
+        if self.generation.min_distance_between_cities < 20.0 {
+            return Err(String::from("min distance between cities should be at least 20"));
+        }
+        if self.generation.city_count < Self::MIN_CITY_COUNT || self.generation.city_count > self.max_city_count() {
+            return Err(format!("city count should be from {} to {}", Self::MIN_CITY_COUNT, self.max_city_count()));
+        }
+        if self.generation.road_length_multiplier <= 0.0 {
+            return Err(String::from("road length multiplier should be positive"));
+        }
+        if self.generation.service_count < Self::MIN_SERVICE_COUNT || self.generation.service_count > Self::MAX_SERVICE_COUNT {
+            return Err(format!("service count should be from {} to {}", Self::MIN_SERVICE_COUNT, Self::MAX_SERVICE_COUNT));
+        }
+
+        Ok(())
+    }
+
+    // Config file consists of lines <key> [<value>]*, unknown keys are errors to catch typos.
+    pub fn apply_config(&mut self, path : &Path) -> Result<(), String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("can't read config {} : {}", path.display(), e))?;
+
+        for line in content.lines() {
+            let tokens : Vec<&str> = line.split_whitespace().collect();
+            if tokens.is_empty() || tokens[0].starts_with('#') { continue; }
+
+            let value = tokens.get(1).ok_or(format!("value is missing in [{}]", line))?;
+            self.apply_property(tokens[0], value)?;
+        }
+
+        Ok(())
+    }
+
+    // Same keys are used by config files and input records.
+    pub fn apply_property(&mut self, key : &str, value : &str) -> Result<(), String> {
+        let incorrect = || format!("incorrect value in [{} {}]", key, value);
+
+        match key {
+            "seed" => { self.seed = WorldSeed::from_code(value)?; }
+            "city_count" => { self.generation.city_count = value.parse().map_err(|_| incorrect())?; }
+            "map_size" => {
+                let size = value.parse().map_err(|_| incorrect())?;
+                self.generation.size = IVec2::new(size, size);
+            }
+            "min_distance_between_cities" => { self.generation.min_distance_between_cities = value.parse().map_err(|_| incorrect())?; }
+            "road_length_multiplier" => { self.generation.road_length_multiplier = value.parse().map_err(|_| incorrect())?; }
+            "service_count" => { self.generation.service_count = value.parse().map_err(|_| incorrect())?; }
+            _ => { return Err(format!("unknown parameter [{}]", key)); }
+        }
+
+        Ok(())
+    }
+
+    // Everything needed to generate the same map again, map is always square.
+    pub fn to_properties(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("seed", self.seed.to_code()),
+            ("city_count", self.generation.city_count.to_string()),
+            ("map_size", self.generation.size.x.to_string()),
+            ("min_distance_between_cities", self.generation.min_distance_between_cities.to_string()),
+            ("road_length_multiplier", self.generation.road_length_multiplier.to_string()),
+            ("service_count", self.generation.service_count.to_string())
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_code_survives_round_trip() {
+        for code in ["0", "7", "RACING", "ZZZZZZZZZZZZ"].iter() {
+            assert_eq!(WorldSeed::from_code(code).unwrap().to_code(), *code);
+        }
+        // Codes are typed with the game font, so letters are read in any case.
+        assert!(WorldSeed::from_code("racing").unwrap() == WorldSeed::from_code("RACING").unwrap());
+    }
+
+    #[test]
+    fn bad_seed_code_is_refused() {
+        assert!(WorldSeed::from_code("").is_err());
+        assert!(WorldSeed::from_code(&"Z".repeat(MAX_SEED_CODE_LENGTH)).is_ok());
+        assert!(WorldSeed::from_code(&"Z".repeat(MAX_SEED_CODE_LENGTH + 1)).is_err());
+        assert!(WorldSeed::from_code("AB-12").is_err());
+        assert!(WorldSeed::from_code("A B").is_err());
+        assert!(WorldSeed::from_code("Ö").is_err());
+    }
+}
@@ -1,4 +1,29 @@
 use crate::game::{Percent};
+use crate::game::save::*;
+use crate::engine::common::Math;
+use crate::game::ride::car_model::DEFAULT_CAR_MODEL;
+use crate::game::city_map::services::{Cargo, ProductType};
+use crate::game::inventory::Inventory;
+use crate::game::ride::car::{Car, CarSystem};
+
+// Survival stats change in percents per hour.
+const HUNGER_PER_HOUR : f32 = 4.0;
+const THIRST_PER_HOUR : f32 = 6.0;
+const TIREDNESS_PER_HOUR : f32 = 5.0;
+const REST_PER_HOUR : f32 = 30.0;
+
+// Survival stats start to affect driving at this level, HUD warns at the next one and player collapses at 100.
+pub const STAT_EFFECT_LEVEL : f32 = 50.0;
+pub const STAT_WARNING_LEVEL : f32 = 75.0;
+
+// Effects of the used products per liter or kilogram.
+const WATER_THIRST : f32 = 20.0;
+const SODA_THIRST : f32 = 10.0;
+const SODA_HUNGER : f32 = 5.0;
+const FOOD_HUNGER : f32 = 20.0;
+const SPARE_PARTS_REPAIR : f32 = 20.0;
+// Oil level is in percents of the full engine.
+pub const OIL_PER_LITER : f32 = 25.0;
 
 #[derive(Clone)]
 pub struct Player {
@@ -7,14 +32,19 @@ pub struct Player {
     pub thirst : Percent,
     pub tireness : Percent,
     
-    pub oil_level : u32,
-    pub max_oil_level : u32,
+    pub oil_level : f32,
+    pub max_oil_level : f32,
 
     pub gas_level : f32,
     pub gas_per_distance : f32,
     pub max_gas_level : f32,
 
-    pub car_damage : Percent,
+    pub car_model : String,
+    // Models bought before stay in the garage, the current one is among them.
+    pub owned_car_models : Vec<String>,
+
+    pub cargo : Vec<Cargo>,
+    pub inventory : Inventory
 }
 
 impl Player {
@@ -25,14 +55,133 @@ impl Player {
             thirst : Percent(0.0),
             tireness : Percent(0.0),
 
-            oil_level : 0,
-            max_oil_level : 100,
+            oil_level : 100.0,
+            max_oil_level : 100.0,
 
-            gas_level : 0.0,
+            gas_level : 30.0,
             max_gas_level : 100.0,
-            gas_per_distance : 1.0,
+            gas_per_distance : 0.1,
+
+            car_model : String::from(DEFAULT_CAR_MODEL),
+            owned_car_models : vec![String::from(DEFAULT_CAR_MODEL)],
+
+            cargo : Vec::new(),
+            inventory : Inventory::new()
+        }
+    }
+
+    pub fn pass_time(&mut self, minutes : f32) {
+        let hours = minutes / 60.0;
+        self.hunger.add(Percent(hours * HUNGER_PER_HOUR));
+        self.thirst.add(Percent(hours * THIRST_PER_HOUR));
+        self.tireness.add(Percent(hours * TIREDNESS_PER_HOUR));
+    }
+
+    // Player gets hungry and thirsty while sleeping too.
+    pub fn rest(&mut self, minutes : f32) {
+        let hours = minutes / 60.0;
+        self.hunger.add(Percent(hours * HUNGER_PER_HOUR));
+        self.thirst.add(Percent(hours * THIRST_PER_HOUR));
+        self.tireness.sub(Percent(hours * REST_PER_HOUR));
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        [self.hunger, self.thirst, self.tireness].iter().any(|stat| stat.0 >= 100.0)
+    }
+
+    // Hospital brings survival stats back to the level where they don't affect driving.
+    pub fn recover(&mut self) {
+        for stat in [&mut self.hunger, &mut self.thirst, &mut self.tireness].iter_mut() {
+            stat.0 = Math::min(stat.0, STAT_EFFECT_LEVEL);
+        }
+    }
 
-            car_damage : Percent(0.0)
+    pub fn damage_cargo(&mut self, damage : f32) {
+        for cargo in &mut self.cargo {
+            cargo.condition.sub(Percent(damage * cargo.fragility));
         }
     }
+
+    // Spare parts fix the most damaged car system.
+    pub fn use_item(&mut self, id : usize, car : &mut Car) {
+        let item = match self.inventory.take(id) {
+            Some(item) => { item }
+            None => { return; }
+        };
+
+        match item {
+            ProductType::Water(size) => { self.thirst.sub(Percent(size * WATER_THIRST)); }
+            ProductType::Soda(size) => { self.thirst.sub(Percent(size * SODA_THIRST)); self.hunger.sub(Percent(size * SODA_HUNGER)); }
+            ProductType::Food(size) => { self.hunger.sub(Percent(size * FOOD_HUNGER)); }
+            ProductType::SpareParts(size) => {
+                let system = CarSystem::all().iter()
+                .min_by(|a, b| car.get_condition(**a).0.partial_cmp(&car.get_condition(**b).0).unwrap())
+                .copied()
+                .unwrap();
+                car.fix_system(system, Percent(size * SPARE_PARTS_REPAIR));
+            }
+            ProductType::Jerrycan(size) => { self.gas_level = Math::min(self.gas_level + size, self.max_gas_level); }
+            ProductType::Oil(size) => { self.oil_level = Math::min(self.oil_level + size * OIL_PER_LITER, self.max_oil_level); }
+        }
+    }
+
+    pub fn save(&self, save : &mut SaveWriter) {
+        save.write("player_money", vec![self.money.to_string()]);
+        save.write("player_hunger", vec![self.hunger.0.to_string()]);
+        save.write("player_thirst", vec![self.thirst.0.to_string()]);
+        save.write("player_tireness", vec![self.tireness.0.to_string()]);
+        save.write("player_oil", vec![self.oil_level.to_string(), self.max_oil_level.to_string()]);
+        save.write("player_gas", vec![self.gas_level.to_string(), self.gas_per_distance.to_string(), self.max_gas_level.to_string()]);
+        save.write("player_car_model", vec![self.car_model.clone()]);
+        save.write("player_owned_car_models", self.owned_car_models.clone());
+        for cargo in &self.cargo {
+            save.write("player_cargo", vec![
+                cargo.destination_city_id.to_string(),
+                cargo.reward.to_string(),
+                cargo.deadline.to_string(),
+                cargo.fragility.to_string(),
+                cargo.condition.0.to_string()
+            ]);
+        }
+        self.inventory.save(save);
+    }
+
+    pub fn load(save : &SaveReader) -> Result<Player, String> {
+        let car_model : String = save.get("player_car_model")?.get(0)?;
+        let owned_car_models = save.get("player_owned_car_models")?.values().to_vec();
+        if !owned_car_models.contains(&car_model) { return Err(format!("car model [{}] isn't owned", car_model)); }
+
+        let oil = save.get("player_oil")?;
+        let gas = save.get("player_gas")?;
+        let mut cargo = Vec::new();
+        for entry in save.get_all("player_cargo") {
+            cargo.push(Cargo {
+                destination_city_id : entry.get(0)?,
+                reward : entry.get(1)?,
+                deadline : entry.get(2)?,
+                fragility : entry.get(3)?,
+                condition : Percent(entry.get(4)?)
+            });
+        }
+
+        Ok(Player {
+            money : save.get("player_money")?.get(0)?,
+            hunger : Percent(save.get("player_hunger")?.get(0)?),
+            thirst : Percent(save.get("player_thirst")?.get(0)?),
+            tireness : Percent(save.get("player_tireness")?.get(0)?),
+
+            oil_level : oil.get(0)?,
+            max_oil_level : oil.get(1)?,
+
+            gas_level : gas.get(0)?,
+            gas_per_distance : gas.get(1)?,
+            max_gas_level : gas.get(2)?,
+
+            car_model,
+            owned_car_models,
+
+            cargo,
+            inventory : Inventory::load(save)?
+        })
+    }
 }
\ No newline at end of file
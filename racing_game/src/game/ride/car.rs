@@ -4,6 +4,66 @@ use image::{RgbImage, RgbaImage};
 
 use crate::engine::common::{IVec2, ImageOps, Math};
 use crate::game::*;
+use crate::game::save::*;
+use super::car_model::CarModel;
+
+// Share of the collision damage taken by every car system.
+const COLLISION_DAMAGE : [(CarSystem, f32); 3] = [(CarSystem::Chase, 1.0), (CarSystem::Wheels, 0.5), (CarSystem::Engine, 0.25)];
+// Damage of the collision at max speed.
+const MAX_COLLISION_DAMAGE : f32 = 30.0;
+const COLLISION_SPEED_LEFT : f32 = 0.2;
+
+// Wear of car systems in percents per road unit driven.
+const DISTANCE_WEAR : [(CarSystem, f32); 6] = [
+    (CarSystem::Wheels, 0.008), (CarSystem::Transmission, 0.005), (CarSystem::Chase, 0.002), 
+    (CarSystem::Engine, 0.005), (CarSystem::Brake, 0.002), (CarSystem::Starter, 0.001)
+];
+// Additional wear per road unit driven off the road, grows with the distance to the road.
+const OFFROAD_WEAR : [(CarSystem, f32); 2] = [(CarSystem::Wheels, 0.2), (CarSystem::Chase, 0.1)];
+// Wear in percents per unit of speed lost while braking hard.
+const HARD_BRAKING_WEAR : [(CarSystem, f32); 2] = [(CarSystem::Brake, 0.3), (CarSystem::Wheels, 0.1)];
+// Engine wear per road unit driven without oil.
+const OIL_STARVATION_WEAR : f32 = 0.5;
+// Braking is hard above this share of max speed.
+const HARD_BRAKING_SPEED : f32 = 0.5;
+
+// Multipliers of characteristics when car system is fully broken, 
+// columns are acceleration, deceleration, brake deceleration, max speed, steer speed and gas mileage.
+const DAMAGE_EFFECTS : [(CarSystem, [f32; 6]); 6] = [
+    (CarSystem::Wheels,       [0.8, 1.5, 0.7, 0.8, 0.5, 0.9]),
+    (CarSystem::Transmission, [0.5, 1.0, 1.0, 0.6, 1.0, 0.8]),
+    (CarSystem::Chase,        [0.9, 1.2, 1.0, 0.9, 0.7, 0.9]),
+    (CarSystem::Engine,       [0.3, 1.0, 1.0, 0.5, 1.0, 0.6]),
+    (CarSystem::Brake,        [1.0, 0.5, 0.3, 1.0, 1.0, 1.0]),
+    (CarSystem::Starter,      [0.8, 1.0, 1.0, 1.0, 1.0, 0.9])
+];
+
+// Reaction delay in seconds of the starving or dehydrated driver.
+const MAX_REACTION_DELAY : f32 = 0.5;
+// Steering drift of the sleeping driver.
+const MAX_DRIFT_SPEED : f32 = 0.8;
+const DRIFT_FREQUENCY : f32 = 0.7;
+
+// Effects of survival stats on driving, both are from 0 to 1.
+#[derive(Clone, Copy)]
+pub struct DriverCondition {
+    pub reaction_loss : f32,
+    pub drowsiness : f32
+}
+
+impl DriverCondition {
+    pub fn normal() -> DriverCondition {
+        DriverCondition { reaction_loss : 0.0, drowsiness : 0.0 }
+    }
+
+    pub fn from_player(player : &Player) -> DriverCondition {
+        let excess = |stat : Percent| Math::max(stat.0 - STAT_EFFECT_LEVEL, 0.0) / (100.0 - STAT_EFFECT_LEVEL);
+        DriverCondition { 
+            reaction_loss : Math::max(excess(player.hunger), excess(player.thirst)), 
+            drowsiness : excess(player.tireness) 
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Characteristics {
@@ -37,12 +97,20 @@ pub struct Car{
     pub roadside_dist : Option<f32>,
     pub x_pos : f32,
     pub width : f32,
+    pub has_gas : bool,
+    // From 0 with enough oil to 1 with the empty engine.
+    oil_starvation : f32,
     image : RgbaImage,
 
     input_horz : Option<InputEvent>,
     prev_input_horz : Option<InputEvent>,
 
-    input_vert : Option<InputEvent>
+    input_vert : Option<InputEvent>,
+
+    driver : DriverCondition,
+    // Input waits here for the driver to react.
+    delayed_input : Vec<(f32, InputEvent, EventType)>,
+    drift_phase : f32
 }
 
 impl Car {
@@ -56,16 +124,26 @@ impl Car {
             roadside_dist : None,
             x_pos : 0.0,
             width,
+            has_gas : true,
+            oil_starvation : 0.0,
 
             image,
 
             input_horz : None,
             prev_input_horz : None,
 
-            input_vert : None
+            input_vert : None,
+
+            driver : DriverCondition::normal(),
+            delayed_input : Vec::new(),
+            drift_phase : 0.0
         }
     }
 
+    pub fn from_model(model : &CarModel) -> Car {
+        Car::new(Game::load_image_rgba(&model.sprite), model.width, model.characteristics.clone())
+    }
+
     pub fn reset(&mut self) {
         self.roadside_dist = None;
         self.input_horz = None;
@@ -73,10 +151,61 @@ impl Car {
         self.prev_input_horz = None;
         self.speed = 0.0;
         self.x_pos = 0.0;
+        self.has_gas = true;
+        self.delayed_input.clear();
+        self.drift_phase = 0.0;
+    }
+
+    pub fn set_driver_condition(&mut self, driver : DriverCondition) {
+        self.driver = driver;
+    }
+
+    pub fn get_driver_condition(&self) -> DriverCondition {
+        self.driver
+    }
+
+    pub fn get_throttle(&self) -> f32 {
+        if self.input_vert == Some(InputEvent::CarGas) && self.can_accelerate() { 1.0 } else { 0.0 }
+    }
+
+    pub fn get_condition(&self, system : CarSystem) -> Percent {
+        *self.damage.car_systems.get(&system).unwrap()
+    }
+
+    pub fn set_oil_starvation(&mut self, oil_starvation : f32) {
+        self.oil_starvation = oil_starvation;
+    }
+
+    // Engine broken down to zero doesn't run anymore.
+    pub fn is_engine_seized(&self) -> bool {
+        self.get_condition(CarSystem::Engine).0 <= 0.0
+    }
+
+    pub fn is_braking(&self) -> bool {
+        self.input_vert == Some(InputEvent::CarBrake) && self.speed > 0.0
+    }
+
+    fn can_accelerate(&self) -> bool {
+        self.has_gas && !self.is_engine_seized()
+    }
+
+    pub fn get_max_speed(&self) -> f32 {
+        self.base_characteristics.max_speed
+    }
+
+    pub fn get_gas_mileage(&self) -> f32 {
+        self.characteristics.gas_mileage
     }
 
     pub fn process_input(&mut self, input : &Vec<(InputEvent, EventType)>) {
+        let delay = self.driver.reaction_loss * MAX_REACTION_DELAY;
         for (event, event_type) in input {
+            self.delayed_input.push((delay, *event, *event_type));
+        }
+    }
+
+    fn apply_input(&mut self, input : &[(f32, InputEvent, EventType)]) {
+        for (_, event, event_type) in input {
             match event_type {
                 EventType::Pressed => { 
                     match event {
@@ -113,11 +242,57 @@ impl Car {
         }
     }
 
+    pub fn save(&self, save : &mut SaveWriter) {
+        for (id, system) in CarSystem::all().iter().enumerate() {
+            let condition = self.damage.car_systems.get(system).unwrap();
+            save.write("car_system", vec![id.to_string(), condition.0.to_string()]);
+        }
+    }
+
+    pub fn load(&mut self, save : &SaveReader) -> Result<(), String> {
+        let mut car_systems = Vec::new();
+        for entry in save.get_all("car_system") {
+            let system = *CarSystem::all().get(entry.get::<usize>(0)?).ok_or(String::from("incorrect car system"))?;
+            car_systems.push((system, Percent(entry.get(1)?)));
+        }
+
+        for (system, condition) in car_systems {
+            self.damage.car_systems.insert(system, condition);
+        }
+
+        Ok(())
+    }
+
     pub fn fix_system(&mut self, system : CarSystem, percent : Percent) {
-        self.damage.car_systems.get_mut(&system).unwrap().0 += percent.0;
+        self.damage.car_systems.get_mut(&system).unwrap().add(percent);
+    }
+
+    // Damage depends on the speed of impact.
+    pub fn collide(&mut self) -> Vec<(CarSystem, Percent)> {
+        let impact = Math::min(self.speed / self.characteristics.max_speed, 1.0);
+        self.speed *= COLLISION_SPEED_LEFT;
+
+        COLLISION_DAMAGE.iter().map(|(system, share)| {
+            let damage = Percent(impact * share * MAX_COLLISION_DAMAGE);
+            self.damage.car_systems.get_mut(system).unwrap().sub(damage);
+            (*system, damage)
+        }).collect()
+    }
+
+    fn wear(&mut self, systems : &[(CarSystem, f32)], amount : f32) {
+        for (system, wear) in systems {
+            self.damage.car_systems.get_mut(system).unwrap().sub(Percent(wear * amount));
+        }
     }
 
     pub fn update(&mut self, delta_time : f32) {
+        self.characteristics = self.damage.affect_characteristics(self.base_characteristics.clone());
+
+        for (delay, _, _) in &mut self.delayed_input { *delay -= delta_time; }
+        let (ready_input, waiting_input) : (Vec<_>, Vec<_>) = self.delayed_input.drain(..).partition(|(delay, _, _)| *delay <= 0.0);
+        self.delayed_input = waiting_input;
+        self.apply_input(&ready_input);
+
         let steer = match self.input_horz {
             Some(InputEvent::CarLeft) => { -1.0 }
             Some(InputEvent::CarRight) => { 1.0 }
@@ -126,8 +301,14 @@ impl Car {
 
         self.x_pos += steer * delta_time * self.characteristics.steer_speed * (self.speed / self.characteristics.max_speed);
 
+        // Drowsy driver drifts from side to side.
+        self.drift_phase += delta_time * DRIFT_FREQUENCY;
+        self.x_pos += self.drift_phase.sin() * self.driver.drowsiness * MAX_DRIFT_SPEED * delta_time * (self.speed / self.characteristics.max_speed);
+
+        let prev_speed = self.speed;
         let acceleration = match self.input_vert {
-            Some(InputEvent::CarGas) => { self.characteristics.acceleration }
+            // Car without gas or with the seized engine coasts to a stop.
+            Some(InputEvent::CarGas) if self.can_accelerate() => { self.characteristics.acceleration }
             Some(InputEvent::CarBrake) => { -self.characteristics.brake_deceleration }
             _ => { -self.characteristics.deceleration }
         };
@@ -143,6 +324,17 @@ impl Car {
         
         if self.speed > max_speed { self.speed = max_speed; }
         if self.speed < 0.0 { self.speed = 0.0 };
+
+        let distance = self.speed * delta_time;
+        self.wear(&DISTANCE_WEAR, distance);
+        self.wear(&[(CarSystem::Engine, OIL_STARVATION_WEAR)], distance * self.oil_starvation);
+        if let Some(roadside_dist) = self.roadside_dist {
+            self.wear(&OFFROAD_WEAR, distance * (1.0 + roadside_dist.abs()));
+        }
+        let is_braking = self.input_vert == Some(InputEvent::CarBrake);
+        if is_braking && prev_speed > self.base_characteristics.max_speed * HARD_BRAKING_SPEED {
+            self.wear(&HARD_BRAKING_WEAR, prev_speed - self.speed);
+        }
     }
 
     pub fn render(&self, image : &mut RgbImage) {
@@ -153,6 +345,7 @@ impl Car {
     }
 }
 
+#[derive(Copy, Clone)]
 enum Characteristic {
     Acceleration,
     Deceleration,
@@ -162,6 +355,15 @@ enum Characteristic {
     GasMileage
 }
 
+impl Characteristic {
+    fn all() -> [Characteristic; 6] {
+        [
+            Characteristic::Acceleration, Characteristic::Deceleration, Characteristic::BrakeDeceleration, 
+            Characteristic::MaxSpeed, Characteristic::SteerSpeed, Characteristic::GasMileage
+        ]
+    }
+}
+
 #[derive(Hash, PartialEq, Eq, Copy, Clone)]
 pub enum CarSystem {
     Wheels,
@@ -172,6 +374,23 @@ pub enum CarSystem {
     Starter
 }
 
+impl CarSystem {
+    pub fn all() -> [CarSystem; 6] {
+        [CarSystem::Wheels, CarSystem::Transmission, CarSystem::Chase, CarSystem::Engine, CarSystem::Brake, CarSystem::Starter]
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            CarSystem::Wheels => { "WHEELS" }
+            CarSystem::Transmission => { "TRANSMISSION" }
+            CarSystem::Chase => { "CHASE" }
+            CarSystem::Engine => { "ENGINE" }
+            CarSystem::Brake => { "BRAKE" }
+            CarSystem::Starter => { "STARTER" }
+        }
+    }
+}
+
 struct DamageEffect {
     characteristic : Characteristic,
     car_system : CarSystem,
@@ -184,7 +403,8 @@ impl DamageEffect {
     }
 
     fn apply(&self, characteristicts : &mut Characteristics, damage : &Damage) {
-        let multiplier = Math::lerp(1.0, self.multiplier_when_fully_broken, damage.car_systems.get(&self.car_system).unwrap().to_norm());
+        // Condition is 100 percents when car system is intact.
+        let multiplier = Math::lerp(self.multiplier_when_fully_broken, 1.0, damage.car_systems.get(&self.car_system).unwrap().to_norm());
         match self.characteristic {
             Characteristic::Acceleration => { characteristicts.acceleration *= multiplier; }
             Characteristic::Deceleration => { characteristicts.deceleration *= multiplier; }
@@ -212,9 +432,12 @@ impl Damage {
         car_systems.insert(CarSystem::Transmission, Percent(100.0));
         car_systems.insert(CarSystem::Wheels, Percent(100.0));
 
-        let mut effects = vec![
-            DamageEffect::new(Characteristic::Deceleration, CarSystem::Brake, 0.5)
-        ];
+        let mut effects = Vec::new();
+        for (car_system, multipliers) in DAMAGE_EFFECTS.iter() {
+            for (characteristic, multiplier) in Characteristic::all().iter().zip(multipliers.iter()) {
+                effects.push(DamageEffect::new(*characteristic, *car_system, *multiplier));
+            }
+        }
 
         Damage { car_systems, effects }
     }
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::str::FromStr;
+
+use crate::game::RESOURCES_DIR;
+use super::car::Characteristics;
+
+const CAR_MODELS_DIR : &str = "cars";
+const CAR_MODEL_EXTENSION : &str = "car";
+pub const DEFAULT_CAR_MODEL : &str = "ferrari";
+
+#[derive(Clone)]
+pub struct CarModel {
+    pub id : String, // Name of the model file.
+    pub name : String,
+    pub sprite : String,
+    pub width : f32,
+    pub price : f32,
+    pub characteristics : Characteristics
+}
+
+impl CarModel {
+    // Model file consists of lines <key> <value>, all keys are required.
+    pub fn parse(id : &str, content : &str) -> Result<CarModel, String> {
+        let mut fields = HashMap::<&str, &str>::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            let mut tokens = line.splitn(2, ' ');
+            let key = tokens.next().unwrap();
+            let value = tokens.next().ok_or(format!("value is missing in [{}]", line))?;
+            fields.insert(key, value.trim());
+        }
+
+        let get = |key : &str| fields.get(key).copied().ok_or(format!("car model {} has no {}", id, key));
+        fn parse<T>(key : &str, value : &str) -> Result<T, String> where T : FromStr {
+            value.parse().map_err(|_| format!("incorrect {} [{}]", key, value))
+        }
+        let characteristic = |key : &str| -> Result<f32, String> { parse(key, get(key)?) };
+
+        Ok(CarModel {
+            id : String::from(id),
+            name : String::from(get("name")?),
+            sprite : String::from(get("sprite")?),
+            width : parse("width", get("width")?)?,
+            price : parse("price", get("price")?)?,
+            characteristics : Characteristics::new(
+                characteristic("acceleration")?,
+                characteristic("deceleration")?,
+                characteristic("brake_deceleration")?,
+                characteristic("max_speed")?,
+                characteristic("steer_speed")?,
+                characteristic("gas_mileage")?
+            )
+        })
+    }
+
+    // Models are sorted by price, so the cheapest one comes first in the garage.
+    pub fn load_all() -> Vec<CarModel> {
+        let dir = RESOURCES_DIR.get_dir(CAR_MODELS_DIR).unwrap_or_else(|| panic!("directory {} not found!", CAR_MODELS_DIR));
+
+        let mut models : Vec<CarModel> = dir.files().iter()
+        .filter(|file| file.path().extension() == Some(OsStr::new(CAR_MODEL_EXTENSION)))
+        .map(|file| {
+            let id = file.path().file_stem().unwrap().to_str().unwrap();
+            let content = file.contents_utf8().unwrap_or_else(|| panic!("car model {} isn't utf8!", id));
+            CarModel::parse(id, content).unwrap_or_else(|e| panic!("can't load car model : {}", e))
+        })
+        .collect();
+
+        models.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap().then(a.id.cmp(&b.id)));
+        models
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL : &str = "# Comment.
+name TEST CAR
+sprite cars/test.png
+width 0.4
+price 100
+acceleration 5.0
+deceleration 1.0
+brake_deceleration 10.0
+max_speed 10.0
+steer_speed 1.5
+gas_mileage 1.0
+";
+
+    #[test]
+    fn car_model_is_parsed() {
+        let model = CarModel::parse("test", MODEL).unwrap();
+        assert_eq!(model.id, "test");
+        assert_eq!(model.name, "TEST CAR");
+        assert_eq!(model.sprite, "cars/test.png");
+        assert_eq!(model.price, 100.0);
+    }
+
+    #[test]
+    fn car_model_without_key_is_refused() {
+        let content = MODEL.replace("max_speed 10.0\n", "");
+        let error = CarModel::parse("test", &content).err().unwrap();
+        assert!(error.contains("max_speed"));
+    }
+
+    #[test]
+    fn car_model_with_bad_number_is_refused() {
+        let content = MODEL.replace("width 0.4", "width wide");
+        let error = CarModel::parse("test", &content).err().unwrap();
+        assert!(error.contains("width"));
+        assert!(CarModel::parse("test", &MODEL.replace("price 100", "price")).is_err());
+    }
+}
@@ -1,19 +1,65 @@
-use std::rc::Rc;
-
-use image::{RgbImage, RgbaImage};
+use image::{RgbImage, RgbaImage, Rgb};
 
 use super::city_map::road_path::RoadPathMeta;
-use super::{Game, Player, SCREEN_RESOLUTION};
+use super::{Game, Player, Percent, SCREEN_RESOLUTION};
 use crate::engine::billboards::*;
 use crate::engine::track::*;
 use crate::engine::horizon::*;
 use crate::engine::camera::*;
 use crate::engine::traffic::*;
-use crate::engine::common::{IVec2, ImageOps};
+use crate::engine::common::{IVec2, ImageOps, Math};
 use super::{EventType, InputEvent};
+use super::save::*;
 
 pub mod car;
 use car::*;
+pub mod car_model;
+use car_model::*;
+
+// Length of the car along the road.
+const COLLISION_DEPTH : f32 = 0.3;
+const COLLISION_KNOCK_BACK : f32 = 0.2;
+// Time to get away from the obstacle before it can be hit again.
+const COLLISION_COOLDOWN : f32 = 0.5;
+
+// Game minutes passing every second of the ride.
+const RIDE_MINUTES_PER_SECOND : f32 = 3.0;
+
+// Drowsy driver blinks more often and keeps eyes closed longer.
+const BLINK_PERIOD : (f32, f32) = (8.0, 2.0);
+const BLINK_DURATION : (f32, f32) = (0.2, 0.8);
+
+// Share of the gas burnt while coasting compared to full throttle.
+const COASTING_THROTTLE : f32 = 0.3;
+
+// Help on the road when the car runs out of gas.
+const TOW_COST_PER_DISTANCE : f32 = 0.3;
+pub const RESCUE_GAS_AMOUNT : f32 = 5.0;
+pub const RESCUE_GAS_COST : f32 = 60.0;
+
+// Arriving before the par time is paid proportionally to the road length.
+const PAR_SPEED : f32 = 6.0;
+const PAR_REWARD_PER_DISTANCE : f32 = 0.2;
+
+// Oil burnt in percents per road unit, engine under load burns more.
+const OIL_PER_DISTANCE : f32 = 0.02;
+const OIL_PER_LOAD : f32 = 0.04;
+// Engine starts to wear faster below this oil level.
+pub const LOW_OIL_LEVEL : f32 = 20.0;
+
+// Seconds a honk or a headlights flash from the traffic stays noticeable.
+const TRAFFIC_SIGNAL_TIME : f32 = 1.5;
+
+// Cargo damage per distance driven off the road, before fragility.
+const OFFROAD_CARGO_DAMAGE : f32 = 0.5;
+
+#[derive(Copy, Clone)]
+pub enum RescueOption {
+    Tow,
+    Refuel,
+    // Carried gas is poured into the tank for free.
+    UseJerrycan
+}
 
 pub struct Ride {
     track : Option<Track>,
@@ -24,6 +70,14 @@ pub struct Ride {
     camera : Camera,
     active : bool,
     paused : bool,
+    collision_cooldown : f32,
+    sky_color : Rgb<u8>,
+    light_color : Rgb<u8>,
+    blink_time : f32,
+    eyes_closure : f32,
+    ride_minutes : f32,
+    honk_time : f32,
+    flash_time : f32,
 
     pub car : Car,
     player : Option<Player>
@@ -31,17 +85,18 @@ pub struct Ride {
 
 pub enum RideEvent {
     Finished,
-    ChangePlayer(Player)
+    ChangePlayer(Player),
+    TimePassed(f32),
+    OutOfGas,
+    EngineSeized,
+    Collapsed
 }
 
 impl Ride {
-    pub fn new() -> Ride {
+    pub fn new(car_model : &CarModel) -> Ride {
         let horizon = Horizon::new(Game::load_image_rgba("horizon.png"));
         let camera = Camera { screen_dist : 1.0, viewport_height : 1.0, y_pos : 1.0, far_plane : 150.0, pitch : 1.5, road_distance : 0.0, x_offset : 0.0 }; 
-        let car_img = Game::load_image_rgba("ferrari.png");
-        let car_width = car_img.width() as f32 / SCREEN_RESOLUTION.x as f32;
-        let car_characteristics = Characteristics::new(5.0, 1.0, 10.0, 10.0, 1.5, 1.0);
-        let car = Car::new(car_img, car_width, car_characteristics);
+        let car = Car::from_model(car_model);
 
         Ride { 
             track : None,
@@ -52,17 +107,43 @@ impl Ride {
             length : 0.0, 
             active : false,
             paused : false,
+            collision_cooldown : 0.0,
+            sky_color : Rgb([0, 0, 0]),
+            light_color : Rgb([255, 255, 255]),
+            blink_time : 0.0,
+            eyes_closure : 0.0,
+            ride_minutes : 0.0,
+            honk_time : 0.0,
+            flash_time : 0.0,
             player : None,
             traffic : None
         }
     }
 
+    // New car comes without damage.
+    pub fn set_car_model(&mut self, car_model : &CarModel) {
+        self.car = Car::from_model(car_model);
+    }
+
+    // Light color tints the whole scene.
+    pub fn set_lighting(&mut self, sky_color : Rgb<u8>, light_color : Rgb<u8>) {
+        self.sky_color = sky_color;
+        self.light_color = light_color;
+    }
+
     pub fn set_paused(&mut self, paused : bool) {
         self.paused = paused;
     }
 
-    pub fn start_ride(&mut self, mut ride_data : RoadPathMeta, player : Player) {
+    pub fn start_ride(&mut self, ride_data : RoadPathMeta, player : Player) {
         self.active = true;
+        self.paused = false;
+        self.collision_cooldown = 0.0;
+        self.blink_time = 0.0;
+        self.eyes_closure = 0.0;
+        self.ride_minutes = 0.0;
+        self.honk_time = 0.0;
+        self.flash_time = 0.0;
         self.camera.road_distance = 0.0;
         self.length = ride_data.length;
 
@@ -70,13 +151,75 @@ impl Ride {
         self.track = Some(Track::new(ride_data.track_data));
 
         self.player = Some(player);
-        let track_rc = unsafe { Rc::from_raw(self.track.as_ref().unwrap() as *const Track) };
-        ride_data.traffic.set_track(track_rc);
         self.traffic = Some(ride_data.traffic);
 
         self.car.reset();
     }
 
+    pub fn stop(&mut self) {
+        self.active = false;
+        self.paused = false;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    // Player is changed outside of the ride by rescue and car damage.
+    pub fn set_player(&mut self, player : Player) {
+        if self.active { self.player = Some(player); }
+    }
+
+    pub fn get_road_distance(&self) -> f32 {
+        self.camera.road_distance
+    }
+
+    pub fn get_length(&self) -> f32 {
+        self.length
+    }
+
+    pub fn get_curvature(&self, road_distance : f32) -> f32 {
+        self.track.as_ref().map_or(0.0, |track| track.data.get_curvature(road_distance))
+    }
+
+    pub fn is_honked_at(&self) -> bool {
+        self.honk_time > 0.0
+    }
+
+    pub fn is_flashed_at(&self) -> bool {
+        self.flash_time > 0.0
+    }
+
+    // Tow takes the car to the destination city.
+    pub fn get_tow_cost(&self) -> f32 {
+        Math::max(self.length - self.camera.road_distance, 0.0) * TOW_COST_PER_DISTANCE
+    }
+
+    fn get_par_minutes(&self) -> f32 {
+        self.length / PAR_SPEED * RIDE_MINUTES_PER_SECOND
+    }
+
+    // Negative when the player is late.
+    pub fn get_par_minutes_left(&self) -> f32 {
+        self.get_par_minutes() - self.ride_minutes
+    }
+
+    pub fn get_arrival_reward(&self) -> f32 {
+        if self.get_par_minutes_left() < 0.0 { 0.0 } else { self.length * PAR_REWARD_PER_DISTANCE }
+    }
+
+    pub fn save(&self, save : &mut SaveWriter) {
+        if !self.active { return; }
+        save.write("ride", vec![self.camera.road_distance.to_string(), self.car.x_pos.to_string(), self.ride_minutes.to_string()]);
+    }
+
+    pub fn set_progress(&mut self, road_distance : f32, x_pos : f32, ride_minutes : f32) {
+        self.ride_minutes = ride_minutes;
+        self.camera.road_distance = road_distance;
+        self.car.x_pos = x_pos;
+        self.camera.x_offset = x_pos;
+    }
+
     pub fn process_input(&mut self, input : &Vec<(InputEvent, EventType)>) {
         if !self.active || self.paused { return; } 
         self.car.process_input(input);
@@ -87,14 +230,57 @@ impl Ride {
 
         self.car.update(delta_time);
 
-        self.traffic.as_mut().unwrap().update(&self.camera, delta_time, &mut self.billboards);
+        let traffic_player = TrafficPlayer {
+            road_distance : self.camera.road_distance + self.camera.screen_dist,
+            x_pos : self.car.x_pos,
+            width : self.car.width,
+            speed : self.car.speed,
+            braking : self.car.is_braking()
+        };
+        let traffic_events = self.traffic.as_mut().unwrap().update(self.track.as_ref().unwrap(), &self.camera, &traffic_player, delta_time, &mut self.billboards);
+        self.honk_time = Math::max(self.honk_time - delta_time, 0.0);
+        self.flash_time = Math::max(self.flash_time - delta_time, 0.0);
+        for event in traffic_events {
+            match event {
+                TrafficEvent::Honk => { self.honk_time = TRAFFIC_SIGNAL_TIME; }
+                TrafficEvent::FlashHeadlights => { self.flash_time = TRAFFIC_SIGNAL_TIME; }
+            }
+        }
 
         let mut events : Vec<RideEvent> = Vec::new();
 
         self.track.as_mut().unwrap().compute_y_data(&self.camera, SCREEN_RESOLUTION.y as u32);
 
-        self.player.as_mut().unwrap().gas_level -= self.car.speed * delta_time;
-        events.push(RideEvent::ChangePlayer(self.player.as_ref().unwrap().clone()));
+        let throttle = Math::max(self.car.get_throttle(), COASTING_THROTTLE);
+        let player = self.player.as_mut().unwrap();
+        let burnt_gas = self.car.speed * delta_time * player.gas_per_distance * throttle / self.car.get_gas_mileage();
+        player.gas_level = Math::max(player.gas_level - burnt_gas, 0.0);
+        self.car.has_gas = player.gas_level > 0.0;
+        if !self.car.has_gas && self.car.speed == 0.0 && !self.car.is_engine_seized() { events.push(RideEvent::OutOfGas); }
+
+        // Load is like engine RPM, it grows with throttle and speed.
+        let load = if self.car.get_max_speed() > 0.0 { throttle * self.car.speed / self.car.get_max_speed() } else { 0.0 };
+        let burnt_oil = self.car.speed * delta_time * (OIL_PER_DISTANCE + OIL_PER_LOAD * load);
+        player.oil_level = Math::max(player.oil_level - burnt_oil, 0.0);
+        self.car.set_oil_starvation(Math::max(1.0 - player.oil_level / LOW_OIL_LEVEL, 0.0));
+        if self.car.is_engine_seized() && self.car.speed == 0.0 { events.push(RideEvent::EngineSeized); }
+
+        let minutes = delta_time * RIDE_MINUTES_PER_SECOND;
+        self.ride_minutes += minutes;
+        player.pass_time(minutes);
+        events.push(RideEvent::TimePassed(minutes));
+
+        if player.is_collapsed() {
+            self.active = false;
+            // Collapse ends the ride, so the car isn't rescued.
+            events.retain(|event| !matches!(event, RideEvent::OutOfGas | RideEvent::EngineSeized));
+            events.push(RideEvent::ChangePlayer(player.clone()));
+            events.push(RideEvent::Collapsed);
+            return events;
+        }
+        if self.car.roadside_dist.is_some() { player.damage_cargo(OFFROAD_CARGO_DAMAGE * self.car.speed * delta_time); }
+        self.car.set_driver_condition(DriverCondition::from_player(player));
+        self.update_blinking(delta_time);
 
         self.car.x_pos -= self.track.as_ref().unwrap().get_horz_speed(&self.camera) * self.car.speed * delta_time * 5.0;
 
@@ -102,23 +288,69 @@ impl Ride {
         let car_right = self.car.x_pos + self.car.width * 0.5;
         self.car.roadside_dist = self.track.as_ref().unwrap().roadside_dist(car_left, car_right, self.camera.road_distance + self.camera.screen_dist);
 
+        self.collision_cooldown = Math::max(self.collision_cooldown - delta_time, 0.0);
+        if self.collision_cooldown == 0.0 {
+            if let Some(damage) = self.process_collision() {
+                let total_damage : f32 = damage.iter().map(|(_, percent)| percent.0).sum();
+                self.player.as_mut().unwrap().damage_cargo(total_damage);
+            }
+        }
+        events.push(RideEvent::ChangePlayer(self.player.as_ref().unwrap().clone()));
+
         self.camera.x_offset = self.car.x_pos;
         self.camera.road_distance += self.car.speed * delta_time;
 
         if self.camera.road_distance >= self.length {
             self.active = false;
-            return vec![RideEvent::Finished];
+            events.push(RideEvent::Finished);
         }
 
         return events;
     }
 
+    fn update_blinking(&mut self, delta_time : f32) {
+        let drowsiness = self.car.get_driver_condition().drowsiness;
+        if drowsiness == 0.0 { 
+            self.eyes_closure = 0.0;
+            return; 
+        }
+
+        let period = Math::lerp(BLINK_PERIOD.0, BLINK_PERIOD.1, drowsiness);
+        let duration = Math::lerp(BLINK_DURATION.0, BLINK_DURATION.1, drowsiness);
+        self.blink_time = (self.blink_time + delta_time) % period;
+        // Eyes close and open again at the end of the period.
+        let blink_start = period - duration;
+        self.eyes_closure = if self.blink_time < blink_start { 0.0 } else { ((self.blink_time - blink_start) / duration * std::f32::consts::PI).sin() };
+    }
+
+    fn process_collision(&mut self) -> Option<Vec<(CarSystem, Percent)>> {
+        let car_road_distance = self.camera.road_distance + self.camera.screen_dist;
+        let obstacle = self.billboards.find_collision(car_road_distance, self.car.x_pos, self.car.width, COLLISION_DEPTH, SCREEN_RESOLUTION.x as u32)?;
+        let (obstacle_distance, obstacle_offset) = (obstacle.road_distance, obstacle.offset);
+
+        // Knock the car back behind the obstacle and aside from it.
+        let behind_obstacle = obstacle_distance - self.camera.screen_dist - COLLISION_DEPTH;
+        self.camera.road_distance = Math::max(Math::min(self.camera.road_distance, behind_obstacle), 0.0);
+        self.car.x_pos += if self.car.x_pos < obstacle_offset { -COLLISION_KNOCK_BACK } else { COLLISION_KNOCK_BACK };
+        self.collision_cooldown = COLLISION_COOLDOWN;
+
+        Some(self.car.collide())
+    }
+
     pub fn render(&self, buffer : &mut RgbImage) {
         if !self.active { return; } 
         
+        ImageOps::fill_with_color(buffer, &self.sky_color);
         self.horizon.render(100, 0.0, buffer);
         self.track.as_ref().unwrap().render_from_y_data(buffer, &self.camera);
         self.billboards.render_all(&self.camera, &self.track.as_ref().unwrap().y_data, buffer);
         self.car.render(buffer);
+        ImageOps::multiply(buffer, &self.light_color);
+
+        // Eyelids close from the top and the bottom of the screen.
+        let eyelid_height = (self.eyes_closure * buffer.height() as f32 * 0.5) as u32;
+        for y in (0..eyelid_height).chain(buffer.height() - eyelid_height..buffer.height()) {
+            for x in 0..buffer.width() { buffer.put_pixel(x, y, Rgb([0, 0, 0])); }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,91 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+const SAVE_HEADER : &str = "RACING_GAME_SAVE";
+pub const SAVE_VERSION : u32 = 12;
+// Map is regenerated from the seed, versions before 12 generated another map for the same seed,
+// so their city and service ids point to the wrong places.
+// Map generation test fails until both versions are raised after a generation change.
+pub const MIN_SAVE_VERSION : u32 = 12;
+
+// Save file is a header line followed by lines of <key> [<value>]*.
+pub struct SaveWriter {
+    content : String
+}
+
+impl SaveWriter {
+    pub fn new() -> SaveWriter {
+        SaveWriter { content : format!("{} {}\n", SAVE_HEADER, SAVE_VERSION) }
+    }
+
+    pub fn write(&mut self, key : &str, values : Vec<String>) {
+        self.content += key;
+        for value in values {
+            self.content += " ";
+            self.content += &value;
+        }
+        self.content += "\n";
+    }
+
+    pub fn save(&self, path : &Path) -> Result<(), String> {
+        fs::write(path, &self.content).map_err(|e| format!("can't write save {} : {}", path.display(), e))
+    }
+}
+
+pub struct SaveEntry {
+    key : String,
+    values : Vec<String>
+}
+
+pub fn parse_value<T>(values : &[String], id : usize) -> Result<T, String> where T : FromStr {
+    values.get(id)
+    .and_then(|value| value.parse::<T>().ok())
+    .ok_or(format!("incorrect value #{} in [{}]", id, values.join(" ")))
+}
+
+impl SaveEntry {
+    pub fn get<T>(&self, id : usize) -> Result<T, String> where T : FromStr {
+        parse_value(&self.values, id).map_err(|e| format!("{} of [{}]", e, self.key))
+    }
+
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+}
+
+pub struct SaveReader {
+    entries : Vec<SaveEntry>
+}
+
+impl SaveReader {
+    pub fn load(path : &Path) -> Result<SaveReader, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("can't read save {} : {}", path.display(), e))?;
+        let mut lines = content.lines();
+
+        let header : Vec<&str> = lines.next().unwrap_or("").split_whitespace().collect();
+        if header.len() != 2 || header[0] != SAVE_HEADER {
+            return Err(format!("{} is not a save file", path.display()));
+        }
+        let version = header[1].parse::<u32>().map_err(|_| String::from("incorrect save version"))?;
+        if !(MIN_SAVE_VERSION..=SAVE_VERSION).contains(&version) {
+            return Err(format!("save version {} isn't supported, supported versions are {} to {}", version, MIN_SAVE_VERSION, SAVE_VERSION));
+        }
+
+        let entries = lines
+        .map(|line| line.split_whitespace().map(String::from).collect::<Vec<String>>())
+        .filter(|tokens| !tokens.is_empty())
+        .map(|mut tokens| SaveEntry { key : tokens.remove(0), values : tokens })
+        .collect();
+
+        Ok(SaveReader { entries })
+    }
+
+    pub fn get(&self, key : &str) -> Result<&SaveEntry, String> {
+        self.entries.iter().find(|entry| entry.key == key).ok_or(format!("[{}] is missing in save", key))
+    }
+
+    pub fn get_all(&self, key : &str) -> Vec<&SaveEntry> {
+        self.entries.iter().filter(|entry| entry.key == key).collect()
+    }
+}
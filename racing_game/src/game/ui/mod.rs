@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::rc::Rc;
 
 use image::RgbImage;
 
@@ -7,6 +6,8 @@ use crate::engine::common::IVec2;
 use super::{Game, EventType, InputEvent, Player};
 use crate::engine::window::Key;
 use super::services::*;
+use super::new_game::NewGameParameters;
+use super::ride::RescueOption;
 
 mod ui_screen;
 use ui_screen::*;
@@ -19,11 +20,17 @@ pub enum UIEvent{
     PreviousScreen,
     ServiceAction(ServiceId, ServiceAction),
     SetRidePaused(bool),
-    BindKey(InputEvent, Key)
+    BindKey(InputEvent, Key),
+    NewGame(NewGameParameters),
+    SelectCar(String),
+    UseItem(usize),
+    Rescue(RescueOption),
+    SaveGame,
+    LoadGame
 }
 
+// Screens only keep their own controls, the game is given to them on every init and update.
 pub struct UI {
-    game : Option<Rc<Game>>,
     ui_screens : HashMap<Screen, Box<dyn UIScreen>>,
     screen_stack : Vec<Screen>
 }
@@ -31,17 +38,16 @@ pub struct UI {
 impl UI {
     pub fn new(resolution : &IVec2) -> UI {
         let ui_screens = create_all_screens(resolution);
-        UI { ui_screens, game : None, screen_stack : vec![Screen::Services] } 
+        UI { ui_screens, screen_stack : vec![Screen::NewGame] } 
     }
 
-    pub fn set_game(&mut self, game : Rc<Game>) {
-        self.game = Some(game);
-        self.ui_screens.get_mut(&self.screen_stack[self.screen_stack.len() - 1]).unwrap().init(self.game.as_ref().unwrap());
+    pub fn init(&mut self, game : &Game) {
+        self.ui_screens.get_mut(&self.screen_stack[self.screen_stack.len() - 1]).unwrap().init(game);
     }
 
-    fn change_screen(&mut self, screen : Screen) {
+    fn change_screen(&mut self, screen : Screen, game : &Game) {
         self.screen_stack.push(screen);
-        self.ui_screens.get_mut(&screen).unwrap().init(self.game.as_ref().unwrap());
+        self.ui_screens.get_mut(&screen).unwrap().init(game);
     }
 
     fn prev_screen(&mut self) {
@@ -52,17 +58,38 @@ impl UI {
         }
     }
 
-    pub fn enter_city(&mut self) {
-        self.change_screen(Screen::Services);
+    pub fn enter_city(&mut self, game : &Game) {
+        self.change_screen(Screen::Services, game);
     }
 
-    pub fn update(&mut self, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
-        let events = self.ui_screens.get_mut(&self.screen_stack[self.screen_stack.len() - 1]).unwrap().update(input, delta_time);
+    pub fn enter_ride(&mut self, game : &Game) {
+        self.change_screen(Screen::Game, game);
+    }
+
+    pub fn enter_rescue(&mut self, game : &Game) {
+        self.change_screen(Screen::Rescue, game);
+    }
+
+    pub fn enter_victory(&mut self, game : &Game) {
+        self.change_screen(Screen::Victory, game);
+    }
+
+    pub fn enter_game_over(&mut self, game : &Game) {
+        self.change_screen(Screen::GameOver, game);
+    }
+
+    // Message is shown on the screen where the action was taken.
+    pub fn show_message(&mut self, message : &str) {
+        self.ui_screens.get_mut(&self.screen_stack[self.screen_stack.len() - 1]).unwrap().show_message(message);
+    }
+
+    pub fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
+        let events = self.ui_screens.get_mut(&self.screen_stack[self.screen_stack.len() - 1]).unwrap().update(game, input, delta_time);
 
         events.into_iter()
         .filter(|event| {
             match event {
-                UIEvent::ChangeScreen(screen) => { self.change_screen(*screen); false }
+                UIEvent::ChangeScreen(screen) => { self.change_screen(*screen, game); false }
                 UIEvent::PreviousScreen => { self.prev_screen(); false }
                 _ => { true }
             }
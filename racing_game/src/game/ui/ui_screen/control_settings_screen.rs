@@ -28,7 +28,6 @@ pub struct ControlSettingsScreen{
     menu : UISelector<MenuEvents>,
     binding_action : Option<MenuEvents>,
     font : Rc<Font>,
-    game : Option<Rc<Game>>,
     refresh_control_names : bool,
     unbound : Option<MenuEvents>
 }
@@ -74,7 +73,7 @@ impl ControlSettingsScreen {
 
         let page = UIPage::new(*resolution, Some(Rgb([0, 0, 0])));
 
-        ControlSettingsScreen { page, menu, binding_action : None, font, game : None, refresh_control_names : false, unbound : None }
+        ControlSettingsScreen { page, menu, binding_action : None, font, refresh_control_names : false, unbound : None }
     }
 
     fn input_event_to_menu_event(event : InputEvent) -> MenuEvents {
@@ -87,9 +86,8 @@ impl ControlSettingsScreen {
         }
     }
 
-    fn refresh_control_names(&mut self) {
+    fn refresh_control_names(&mut self, game : &Game) {
         self.page.clear_controls();
-        let game = self.game.as_ref().unwrap();
 
         let actions = vec![InputEvent::CarLeft, InputEvent::CarRight, InputEvent::CarGas, InputEvent::CarBrake];
         let positions = vec![40, 20, 0, -20];
@@ -107,15 +105,13 @@ impl ControlSettingsScreen {
 
 impl UIScreen for ControlSettingsScreen {
     fn init(&mut self, game : &Game) {
-        self.game = unsafe { Some(Rc::from_raw(game as *const Game)) };
-
-        self.refresh_control_names();
+        self.refresh_control_names(game);
     }   
 
-    fn update(&mut self, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
+    fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
         if self.refresh_control_names {
             self.refresh_control_names = false;
-            self.refresh_control_names();
+            self.refresh_control_names(game);
         }
 
         for (event, event_type) in input {
@@ -140,7 +136,7 @@ impl UIScreen for ControlSettingsScreen {
                             _ => { 
                                 self.binding_action = Some(menu_event); 
                                 self.unbound = Some(menu_event);
-                                self.refresh_control_names();
+                                self.refresh_control_names(game);
                             }
                         }
                         continue;
@@ -0,0 +1,108 @@
+use std::rc::Rc;
+
+use image::{RgbImage, Rgb};
+
+use crate::engine::common::IVec2;
+use crate::engine::ui::font::*;
+use crate::engine::ui::*;
+use crate::game::*;
+use crate::game::ui::{UIEvent, Screen};
+
+use super::UIScreen;
+
+#[derive(Copy, Clone)]
+enum MenuEvents {
+    NewGame,
+    Load
+}
+
+// Shown when the player reaches the finish city or goes broke on the road.
+pub struct GameEndScreen {
+    title : String,
+    page : UIPage,
+    message_page : UIPage,
+    menu : UISelector<MenuEvents>,
+    font : Rc<Font>,
+    resolution : IVec2
+}
+
+impl GameEndScreen {
+    pub fn new(resolution : &IVec2, font : Rc<Font>, title : &str) -> GameEndScreen {
+        let pointer_image = Game::load_image_rgba("ui/pointer.png");
+
+        let menu_items = vec![
+            UISelectorItem::new(
+                Box::from(UIText::new(font.clone(), String::from("NEW GAME"))),
+                ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, -60) },
+                MenuEvents::NewGame
+            ),
+            UISelectorItem::new(
+                Box::from(UIText::new(font.clone(), String::from("LOAD GAME"))),
+                ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, -80) },
+                MenuEvents::Load
+            )
+        ];
+
+        let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
+        let menu = UISelector::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, *resolution, None);
+
+        GameEndScreen { title : String::from(title), page : UIPage::new(*resolution, Some(Rgb([0, 0, 0]))), message_page : UIPage::new(*resolution, None), menu, font, resolution : *resolution }
+    }
+}
+
+impl UIScreen for GameEndScreen {
+    fn init(&mut self, game : &Game) {
+        let clock = game.get_clock();
+        let time = clock.get_time_of_day();
+        let lines = vec![
+            self.title.clone(),
+            format!("DAY {} {}.{:02}", clock.get_day(), time.hr, time.min),
+            format!("MONEY ${}", game.player.money.floor())
+        ];
+
+        self.page = UIPage::new(self.resolution, Some(Rgb([0, 0, 0])));
+        self.message_page.clear_controls();
+        for (i, line) in lines.into_iter().enumerate() {
+            self.page.add_control(
+                Box::from(UIText::new(self.font.clone(), line)),
+                &ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, 60 - 20 * i as isize) }
+            );
+        }
+    }
+
+    fn update(&mut self, _game : &Game, input : &Vec<(InputEvent, EventType)>, _delta_time : f32) -> Vec<UIEvent> {
+        for (event, event_type) in input {
+            match (event, event_type) {
+                (InputEvent::UIDown, EventType::Pressed) => { self.menu.select_next_in_direction(&IVec2::new(0, -1)); }
+                (InputEvent::UIUp, EventType::Pressed) => { self.menu.select_next_in_direction(&IVec2::new(0, 1)); }
+                (InputEvent::UISelect, EventType::Pressed) => {
+                    match self.menu.select_current() {
+                        MenuEvents::NewGame => { return vec![UIEvent::ChangeScreen(Screen::NewGame)]; }
+                        MenuEvents::Load => { return vec![UIEvent::LoadGame]; }
+                    }
+                }
+                _ => { }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn render(&self, buffer : &mut RgbImage) {
+        self.page.draw(buffer);
+        self.menu.draw(buffer);
+        self.message_page.draw(buffer);
+    }
+
+    // Load errors are shown under the menu.
+    fn show_message(&mut self, message : &str) {
+        self.message_page.clear_controls();
+        let lines = self.font.wrap(&self.font.to_printable(message), self.resolution.x as u32 - 40);
+        for (i, line) in lines.into_iter().enumerate() {
+            self.message_page.add_control(
+                Box::from(UIText::new(self.font.clone(), line)),
+                &ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, -120 - 20 * i as isize) }
+            );
+        }
+    }
+}
@@ -13,12 +13,18 @@ use super::UIScreen;
 #[derive(Clone)]
 enum MenuEvents {
     Resume,
+    Inventory,
+    Save,
+    Load,
     Settings,
     Exit
 }
 
 pub struct GameMenuScreen{
-    menu : UISelector<MenuEvents>
+    menu : UISelector<MenuEvents>,
+    message_page : UIPage,
+    font : Rc<Font>,
+    resolution : IVec2
 }
 
 impl GameMenuScreen {
@@ -37,12 +43,42 @@ impl GameMenuScreen {
             }, 
             MenuEvents::Resume)
         );
+        // Inventory.
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(font.clone(), String::from("INVENTORY"))), 
+            ControlProperties { 
+                pivot : Pivot::Center, 
+                position : IVec2::new(320, 180), 
+                binding : Binding::LeftBottom 
+            }, 
+            MenuEvents::Inventory)
+        );
+        // Save.
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(font.clone(), String::from("SAVE GAME"))), 
+            ControlProperties { 
+                pivot : Pivot::Center, 
+                position : IVec2::new(320, 160), 
+                binding : Binding::LeftBottom 
+            }, 
+            MenuEvents::Save)
+        );
+        // Load.
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(font.clone(), String::from("LOAD GAME"))), 
+            ControlProperties { 
+                pivot : Pivot::Center, 
+                position : IVec2::new(320, 140), 
+                binding : Binding::LeftBottom 
+            }, 
+            MenuEvents::Load)
+        );
         // Settings.
         menu_items.push(UISelectorItem::new(
             Box::from(UIText::new(font.clone(), String::from("SETTINGS"))), 
             ControlProperties { 
                 pivot : Pivot::Center, 
-                position : IVec2::new(320, 180), 
+                position : IVec2::new(320, 120), 
                 binding : Binding::LeftBottom 
             }, 
             MenuEvents::Settings)
@@ -52,7 +88,7 @@ impl GameMenuScreen {
             Box::from(UIText::new(font.clone(), String::from("EXIT TO MENU"))), 
             ControlProperties { 
                 pivot : Pivot::Center, 
-                position : IVec2::new(320, 160), 
+                position : IVec2::new(320, 100), 
                 binding : Binding::LeftBottom 
             }, 
             MenuEvents::Exit)
@@ -61,16 +97,16 @@ impl GameMenuScreen {
         let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
         let menu = UISelector::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, resolution.clone(), None);
 
-        GameMenuScreen { menu }
+        GameMenuScreen { menu, message_page : UIPage::new(*resolution, None), font, resolution : *resolution }
     }
 }
 
 impl UIScreen for GameMenuScreen {
     fn init(&mut self, game : &Game) {
-
+        self.message_page.clear_controls();
     }   
 
-    fn update(&mut self, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
+    fn update(&mut self, _game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
         for (event, event_type) in input {
             match (event, event_type) {
                 (InputEvent::UIDown, EventType::Pressed) => { self.menu.select_next_in_direction(&IVec2::new(0, -1)); }
@@ -79,8 +115,11 @@ impl UIScreen for GameMenuScreen {
                     let menu_event = self.menu.select_current();
                     match menu_event {
                         MenuEvents::Resume => { return vec![UIEvent::ChangeScreen(Screen::Game), UIEvent::SetRidePaused(false)]; },
+                        MenuEvents::Inventory => { return vec![UIEvent::ChangeScreen(Screen::Inventory)]; },
+                        MenuEvents::Save => { return vec![UIEvent::SaveGame]; },
+                        MenuEvents::Load => { return vec![UIEvent::LoadGame]; },
                         MenuEvents::Settings => { return vec![UIEvent::ChangeScreen(Screen::Settings)]; },
-                        MenuEvents::Exit => { return vec![]; },
+                        MenuEvents::Exit => { return vec![UIEvent::ChangeScreen(Screen::NewGame)]; },
                     }
                 }
                 (InputEvent::UIMenu, EventType::Pressed) => { return vec![UIEvent::PreviousScreen, UIEvent::SetRidePaused(false)]; }
@@ -93,5 +132,18 @@ impl UIScreen for GameMenuScreen {
 
     fn render(&self, buffer : &mut RgbImage) {
         self.menu.draw(buffer);
+        self.message_page.draw(buffer);
+    }
+
+    // Save and load results are shown under the menu.
+    fn show_message(&mut self, message : &str) {
+        self.message_page.clear_controls();
+        let lines = self.font.wrap(&self.font.to_printable(message), self.resolution.x as u32 - 40);
+        for (i, line) in lines.into_iter().enumerate() {
+            self.message_page.add_control(
+                Box::from(UIText::new(self.font.clone(), line)),
+                &ControlProperties { pivot : Pivot::Center, binding : Binding::LeftBottom, position : IVec2::new(320, 60 - 20 * i as isize) }
+            );
+        }
     }
 }
\ No newline at end of file
@@ -1,39 +1,148 @@
 use std::rc::Rc;
 
-use image::{RgbImage, RgbaImage, Rgb};
+use image::{RgbImage, Rgb};
 
-use crate::engine::common::{IVec2, ImageOps};
+use crate::engine::common::{IVec2, Vec2, Math};
 use crate::engine::ui::font::*;
 use crate::engine::ui::*;
 use crate::game::*;
+use crate::game::ride::{Ride, LOW_OIL_LEVEL};
+use crate::game::ride::car::CarSystem;
 use crate::game::ui::{UIEvent, Screen};
 
 use super::UIScreen;
 
 pub struct GameScreen{
     page : UIPage,
-    game : Option<Rc<Game>>,
     font : Rc<Font>
 }
 
+// Road units to the units shown to the player.
+const SPEED_TO_KMH : f32 = 20.0;
+const DISTANCE_TO_KM : f32 = 0.1;
+
+const BAR_SIZE : IVec2 = IVec2 { x : 100, y : 6 };
+const BAR_COLOR : Rgb<u8> = Rgb([230, 230, 230]);
+const BAR_WARNING_COLOR : Rgb<u8> = Rgb([230, 40, 40]);
+const BAR_BACKGROUND_COLOR : Rgb<u8> = Rgb([60, 60, 60]);
+// Bars turn red below this fill.
+const BAR_WARNING_FILL : f32 = 0.2;
+const WARNING_LIGHT_SIZE : IVec2 = IVec2 { x : 12, y : 12 };
+
+// Minimap shows the road ahead, the car is at the bottom.
+const MINIMAP_SIZE : IVec2 = IVec2 { x : 40, y : 120 };
+const MINIMAP_DISTANCE : f32 = 150.0;
+const MINIMAP_STEP : f32 = 5.0;
+const MINIMAP_HALF_WIDTH : f32 = 20.0;
+
 impl GameScreen {
     pub fn new(resolution : &IVec2, font : Rc<Font>) -> GameScreen {
-        GameScreen { page : UIPage::new(resolution.clone(), None), game : None, font }
+        GameScreen { page : UIPage::new(resolution.clone(), None), font }
+    }
+
+    fn add_text(&mut self, text : String, position : IVec2, pivot : Pivot, binding : Binding) {
+        let label = UIText::new(self.font.clone(), text);
+        self.page.add_control(Box::from(label), &ControlProperties { position, pivot, binding });
+    }
+
+    fn add_bar(&mut self, fill : f32, position : IVec2, pivot : Pivot, binding : Binding) {
+        let color = if fill < BAR_WARNING_FILL { BAR_WARNING_COLOR } else { BAR_COLOR };
+        let bar = UIBar::new(BAR_SIZE, fill, color, BAR_BACKGROUND_COLOR);
+        self.page.add_control(Box::from(bar), &ControlProperties { position, pivot, binding });
+    }
+
+    // Road is bent by curvatures, so the heading changes twice as fast as curvature strength.
+    fn get_minimap_points(ride : &Ride) -> Vec<Vec2> {
+        let mut points = Vec::new();
+        let mut offset = 0.0;
+        let mut heading = 0.0;
+        let mut distance = 0.0;
+        while distance <= MINIMAP_DISTANCE {
+            let road_distance = ride.get_road_distance() + distance;
+            if road_distance > ride.get_length() { break; }
+
+            points.push(Vec2::new(Math::min(Math::max(0.5 + offset / MINIMAP_HALF_WIDTH * 0.5, 0.0), 1.0), distance / MINIMAP_DISTANCE));
+            heading += 2.0 * ride.get_curvature(road_distance) * MINIMAP_STEP;
+            offset += heading * MINIMAP_STEP;
+            distance += MINIMAP_STEP;
+        }
+
+        points
+    }
+
+    fn build_hud(&mut self, game : &Game) {
+        self.page.clear_controls();
+        let car = &game.ride.car;
+        let player = &game.player;
+
+        // Speedometer.
+        self.add_text(format!("{} KMH", (car.speed * SPEED_TO_KMH).round()), IVec2::new(10, -10), Pivot::LeftTop, Binding::LeftTop);
+        // Car model files can set zero max speed.
+        let speed_part = if car.get_max_speed() > 0.0 { car.speed / car.get_max_speed() } else { 0.0 };
+        self.add_bar(speed_part, IVec2::new(10, -26), Pivot::LeftTop, Binding::LeftTop);
+
+        // Time left to get the arrival reward.
+        let par_minutes_left = game.ride.get_par_minutes_left() as u32;
+        let par = if game.ride.get_par_minutes_left() < 0.0 { String::from("LATE") } else { format!("PAR {}.{:02}", par_minutes_left / 60, par_minutes_left % 60) };
+        self.add_text(par, IVec2::new(10, -40), Pivot::LeftTop, Binding::LeftTop);
+
+        // Warn before the player collapses.
+        let warnings = [(player.hunger, "HUNGRY"), (player.thirst, "THIRSTY"), (player.tireness, "TIRED")];
+        let warnings : Vec<&str> = warnings.iter().filter(|(stat, _)| stat.0 >= STAT_WARNING_LEVEL).map(|(_, warning)| *warning).collect();
+        for (i, warning) in warnings.into_iter().enumerate() {
+            self.add_text(String::from(warning), IVec2::new(10, -60 - 20 * i as isize), Pivot::LeftTop, Binding::LeftTop);
+        }
+
+        let clock = game.get_clock();
+        let time = clock.get_time_of_day();
+        self.add_text(format!("DAY {} {}.{:02}", clock.get_day(), time.hr, time.min), IVec2::new(-10, -10), Pivot::RightTop, Binding::RightTop);
+
+        // Trip progress.
+        let distance_left = Math::max(game.ride.get_length() - game.ride.get_road_distance(), 0.0);
+        self.add_text(format!("{:.1} KM LEFT", distance_left * DISTANCE_TO_KM), IVec2::new(-10, -30), Pivot::RightTop, Binding::RightTop);
+        self.add_bar(game.ride.get_road_distance() / game.ride.get_length(), IVec2::new(-10, -46), Pivot::RightTop, Binding::RightTop);
+
+        let minimap = UIPolyline::new(MINIMAP_SIZE, Self::get_minimap_points(&game.ride), BAR_COLOR, Some(BAR_BACKGROUND_COLOR));
+        self.page.add_control(Box::from(minimap), &ControlProperties { position : IVec2::new(-10, -60), pivot : Pivot::RightTop, binding : Binding::RightTop });
+
+        // Fuel gauge.
+        self.add_text(String::from("FUEL"), IVec2::new(-10, 26), Pivot::RightBottom, Binding::RightBottom);
+        self.add_bar(player.gas_level / player.max_gas_level, IVec2::new(-10, 10), Pivot::RightBottom, Binding::RightBottom);
+
+        // Oil warning light.
+        if player.oil_level < LOW_OIL_LEVEL {
+            let light = UIBar::new(WARNING_LIGHT_SIZE, 1.0, BAR_WARNING_COLOR, BAR_BACKGROUND_COLOR);
+            self.page.add_control(Box::from(light), &ControlProperties { position : IVec2::new(-10, 66), pivot : Pivot::RightBottom, binding : Binding::RightBottom });
+            self.add_text(String::from("OIL"), IVec2::new(-28, 66), Pivot::RightBottom, Binding::RightBottom);
+        }
+
+        // Signals from the traffic.
+        let signals = [(game.ride.is_honked_at(), "HONK"), (game.ride.is_flashed_at(), "HEADLIGHTS")];
+        let signals : Vec<&str> = signals.iter().filter(|(active, _)| *active).map(|(_, signal)| *signal).collect();
+        for (i, signal) in signals.into_iter().enumerate() {
+            self.add_text(String::from(signal), IVec2::new(0, 100 - 20 * i as isize), Pivot::Center, Binding::Center);
+        }
+
+        // The most damaged cargo.
+        if let Some(condition) = player.cargo.iter().map(|cargo| cargo.condition.0).min_by(|a, b| a.partial_cmp(b).unwrap()) {
+            self.add_text(format!("CARGO {}%", condition.floor()), IVec2::new(-10, 46), Pivot::RightBottom, Binding::RightBottom);
+        }
+
+        // Condition of car systems.
+        for (i, system) in CarSystem::all().iter().rev().enumerate() {
+            let y = 10 + 14 * i as isize;
+            self.add_bar(car.get_condition(*system).to_norm(), IVec2::new(10, y + 3), Pivot::LeftBottom, Binding::LeftBottom);
+            self.add_text(String::from(system.get_name()), IVec2::new(120, y), Pivot::LeftBottom, Binding::LeftBottom);
+        }
     }
 }
 
 impl UIScreen for GameScreen {
-    fn init(&mut self, game : &Game) {
-        self.game = unsafe { Some(Rc::from_raw(game as *const Game)) };
+    fn init(&mut self, _game : &Game) {
     }   
 
-    fn update(&mut self, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
-        self.page.clear_controls();
-
-        let speed = self.game.as_ref().unwrap().ride.car.speed;
-        let speed_label = UIText::new(self.font.clone(), String::from(format!("{} MPH", speed)));
-        
-        self.page.add_control(Box::from(speed_label), &ControlProperties { position : IVec2::new(10, -10), pivot : Pivot::LeftTop, binding : Binding::LeftTop });
+    fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
+        self.build_hud(game);
 
         for (event, event_type) in input {
             match (event, event_type) {
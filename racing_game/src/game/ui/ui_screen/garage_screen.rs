@@ -0,0 +1,175 @@
+use std::rc::Rc;
+
+use image::{RgbImage, RgbaImage, Rgb};
+
+use crate::engine::common::IVec2;
+use crate::engine::ui::font::*;
+use crate::engine::ui::*;
+use crate::game::*;
+use crate::game::ride::car_model::CarModel;
+use crate::game::ui::UIEvent;
+
+use super::UIScreen;
+
+#[derive(Copy, Clone)]
+enum MenuEvents {
+    Select(usize),
+    Back
+}
+
+pub struct GarageScreen {
+    page : UIPage,
+    info_page : UIPage,
+    message_page : UIPage,
+    menu : Option<UISelector<MenuEvents>>,
+    font : Rc<Font>,
+    resolution : IVec2,
+
+    models : Vec<CarModel>,
+    sprites : Vec<Rc<RgbaImage>>,
+    player_car_model : String,
+    owned_car_models : Vec<String>
+}
+
+impl GarageScreen {
+    pub fn new(resolution : &IVec2, font : Rc<Font>) -> GarageScreen {
+        GarageScreen {
+            page : UIPage::new(*resolution, Some(Rgb([0, 0, 0]))),
+            info_page : UIPage::new(*resolution, None),
+            message_page : UIPage::new(*resolution, None),
+            menu : None,
+            font,
+            resolution : *resolution,
+            models : Vec::new(),
+            sprites : Vec::new(),
+            player_car_model : String::new(),
+            owned_car_models : Vec::new()
+        }
+    }
+
+    fn add_text(page : &mut UIPage, font : &Rc<Font>, text : String, properties : ControlProperties) {
+        page.add_control(Box::from(UIText::new(font.clone(), text)), &properties);
+    }
+
+    // Sprite and characteristics of the highlighted model.
+    fn refresh_info(&mut self) {
+        self.info_page.clear_controls();
+        let model_id = match self.menu.as_mut().unwrap().select_current() {
+            MenuEvents::Select(model_id) => { model_id }
+            MenuEvents::Back => { return; }
+        };
+        let model = &self.models[model_id];
+
+        self.info_page.add_control(
+            Box::from(UIImage::new(self.sprites[model_id].clone())),
+            &ControlProperties { pivot : Pivot::RightTop, binding : Binding::RightTop, position : IVec2::new(-20, -40) }
+        );
+
+        let characteristics = &model.characteristics;
+        let price = if model.id == self.player_car_model {
+            String::from("YOUR CAR")
+        } else if self.owned_car_models.contains(&model.id) {
+            String::from("IN GARAGE")
+        } else {
+            format!("PRICE ${}", model.price)
+        };
+        let lines = vec![
+            price,
+            format!("MAX SPEED {}", characteristics.max_speed),
+            format!("ACCELERATION {}", characteristics.acceleration),
+            format!("BRAKES {}", characteristics.brake_deceleration),
+            format!("STEERING {}", characteristics.steer_speed),
+            format!("MILEAGE {}", characteristics.gas_mileage)
+        ];
+        for (i, line) in lines.into_iter().enumerate() {
+            let position = IVec2::new(-20, -200 - 20 * i as isize);
+            Self::add_text(&mut self.info_page, &self.font, line, ControlProperties { pivot : Pivot::RightTop, binding : Binding::RightTop, position });
+        }
+    }
+}
+
+impl UIScreen for GarageScreen {
+    fn init(&mut self, game : &Game) {
+        self.models = game.get_car_models().clone();
+        self.sprites = self.models.iter().map(|model| Rc::from(Game::load_image_rgba(&model.sprite))).collect();
+        self.player_car_model = String::from(game.get_player_car_model());
+        self.owned_car_models = game.player.owned_car_models.clone();
+
+        self.page.clear_controls();
+        self.message_page.clear_controls();
+        let money = format!("MONEY ${}", game.player.money.floor());
+        Self::add_text(&mut self.page, &self.font, money, ControlProperties { pivot : Pivot::RightTop, binding : Binding::RightTop, position : IVec2::new(-20, -20) });
+
+        let mut menu_items = Vec::new();
+
+        // Cars are bought in the city where the journey starts.
+        if game.city_map.is_in_start_city() {
+            for (i, model) in self.models.iter().enumerate() {
+                menu_items.push(UISelectorItem::new(
+                    Box::from(UIText::new(self.font.clone(), model.name.clone())),
+                    ControlProperties { pivot : Pivot::LeftTop, position : IVec2::new(20, -20 - 20 * i as isize), binding : Binding::LeftTop },
+                    MenuEvents::Select(i)
+                ));
+            }
+        } else {
+            let text = String::from("GARAGE IS ONLY IN THE START CITY");
+            Self::add_text(&mut self.page, &self.font, text, ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::zero() });
+        }
+
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(self.font.clone(), String::from("BACK"))),
+            ControlProperties { pivot : Pivot::LeftBottom, position : IVec2::new(20, 20), binding : Binding::LeftBottom },
+            MenuEvents::Back
+        ));
+
+        let pointer_image = Game::load_image_rgba("ui/pointer.png");
+        let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
+        // Screen is refreshed after a car is taken, the same model stays highlighted.
+        let selected_item = self.menu.as_ref().map_or(0, |menu| menu.get_selected_item());
+        self.menu = Some(UISelector::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution, None));
+        self.menu.as_mut().unwrap().set_selected_item(selected_item);
+
+        self.refresh_info();
+    }
+
+    fn update(&mut self, _game : &Game, input : &Vec<(InputEvent, EventType)>, _delta_time : f32) -> Vec<UIEvent> {
+        for (event, event_type) in input {
+            match (event, event_type) {
+                (InputEvent::UIDown, EventType::Pressed) => {
+                    self.menu.as_mut().unwrap().select_next_in_direction(&IVec2::new(0, -1));
+                    self.refresh_info();
+                }
+                (InputEvent::UIUp, EventType::Pressed) => {
+                    self.menu.as_mut().unwrap().select_next_in_direction(&IVec2::new(0, 1));
+                    self.refresh_info();
+                }
+                (InputEvent::UISelect, EventType::Pressed) => {
+                    match self.menu.as_mut().unwrap().select_current() {
+                        MenuEvents::Select(model_id) => {
+                            // Screen stays open to show the new car or why it can't be taken.
+                            return vec![UIEvent::SelectCar(self.models[model_id].id.clone())];
+                        }
+                        MenuEvents::Back => { return vec![UIEvent::PreviousScreen]; }
+                    }
+                }
+                (InputEvent::UIBack, EventType::Pressed) => { return vec![UIEvent::PreviousScreen]; }
+                _ => { }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn render(&self, buffer : &mut RgbImage) {
+        self.page.draw(buffer);
+        self.info_page.draw(buffer);
+        self.message_page.draw(buffer);
+        self.menu.as_ref().unwrap().draw(buffer);
+    }
+
+    fn show_message(&mut self, message : &str) {
+        self.message_page.clear_controls();
+        let properties = ControlProperties { pivot : Pivot::LeftBottom, binding : Binding::LeftBottom, position : IVec2::new(20, 40) };
+        Self::add_text(&mut self.message_page, &self.font, self.font.to_printable(message), properties);
+    }
+}
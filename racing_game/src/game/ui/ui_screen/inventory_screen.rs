@@ -0,0 +1,134 @@
+use std::rc::Rc;
+
+use image::{RgbImage, Rgb};
+
+use crate::engine::common::IVec2;
+use crate::engine::ui::font::*;
+use crate::engine::ui::*;
+use crate::game::*;
+use crate::game::ui::UIEvent;
+
+use super::UIScreen;
+
+#[derive(Copy, Clone)]
+enum MenuEvents {
+    Use(usize),
+    Back
+}
+
+pub struct InventoryScreen {
+    page : UIPage,
+    menu : Option<UISelector<MenuEvents>>,
+    font : Rc<Font>,
+    resolution : IVec2,
+    // Items shown in the list are outdated after one is used.
+    refresh_items : bool
+}
+
+impl InventoryScreen {
+    pub fn new(resolution : &IVec2, font : Rc<Font>) -> InventoryScreen {
+        InventoryScreen {
+            page : UIPage::new(*resolution, Some(Rgb([0, 0, 0]))),
+            menu : None,
+            font,
+            resolution : *resolution,
+            refresh_items : false
+        }
+    }
+
+    fn add_text(&mut self, text : String, properties : ControlProperties) {
+        self.page.add_control(Box::from(UIText::new(self.font.clone(), text)), &properties);
+    }
+
+    // Same items are shown in one line, using it uses the first of them.
+    fn build_items(&mut self, game : &Game, selected_item : usize) {
+        let inventory = &game.player.inventory;
+
+        self.page.clear_controls();
+        let load = format!("LOAD {} OF {}", inventory.get_load(), inventory.capacity);
+        self.add_text(load, ControlProperties { pivot : Pivot::RightTop, binding : Binding::RightTop, position : IVec2::new(-20, -20) });
+
+        let mut groups : Vec<(usize, usize)> = Vec::new();
+        for (id, item) in inventory.items.iter().enumerate() {
+            let same = |&(first_id, _) : &(usize, usize)| {
+                let first = &inventory.items[first_id];
+                first.get_save_key() == item.get_save_key() && first.get_size() == item.get_size()
+            };
+            match groups.iter().position(same) {
+                Some(group_id) => { groups[group_id].1 += 1; }
+                None => { groups.push((id, 1)); }
+            }
+        }
+
+        let mut menu_items = Vec::new();
+        for (i, (first_id, count)) in groups.into_iter().enumerate() {
+            let item = &inventory.items[first_id];
+            let text = format!("{} {}{} X{}", item.get_name(), item.get_size(), item.get_unit(), count);
+            menu_items.push(UISelectorItem::new(
+                Box::from(UIText::new(self.font.clone(), text)),
+                ControlProperties { pivot : Pivot::LeftTop, position : IVec2::new(20, -20 - 20 * i as isize), binding : Binding::LeftTop },
+                MenuEvents::Use(first_id)
+            ));
+        }
+
+        if menu_items.is_empty() {
+            let text = String::from("INVENTORY IS EMPTY");
+            self.add_text(text, ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::zero() });
+        }
+
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(self.font.clone(), String::from("BACK"))),
+            ControlProperties { pivot : Pivot::LeftBottom, position : IVec2::new(20, 20), binding : Binding::LeftBottom },
+            MenuEvents::Back
+        ));
+
+        // Last item of the list might have been used up.
+        let selected_item = if selected_item + 1 < menu_items.len() { selected_item } else { 0 };
+
+        let pointer_image = Game::load_image_rgba("ui/pointer.png");
+        let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
+        let mut menu = UISelector::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution, None);
+        menu.set_selected_item(selected_item);
+        self.menu = Some(menu);
+    }
+}
+
+impl UIScreen for InventoryScreen {
+    fn init(&mut self, game : &Game) {
+        self.refresh_items = false;
+        self.build_items(game, 0);
+    }
+
+    fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, _delta_time : f32) -> Vec<UIEvent> {
+        if self.refresh_items {
+            self.refresh_items = false;
+            let selected_item = self.menu.as_ref().unwrap().get_selected_item();
+            self.build_items(game, selected_item);
+        }
+
+        for (event, event_type) in input {
+            match (event, event_type) {
+                (InputEvent::UIDown, EventType::Pressed) => { self.menu.as_mut().unwrap().select_next_in_direction(&IVec2::new(0, -1)); }
+                (InputEvent::UIUp, EventType::Pressed) => { self.menu.as_mut().unwrap().select_next_in_direction(&IVec2::new(0, 1)); }
+                (InputEvent::UISelect, EventType::Pressed) => {
+                    match self.menu.as_mut().unwrap().select_current() {
+                        MenuEvents::Use(item_id) => {
+                            self.refresh_items = true;
+                            return vec![UIEvent::UseItem(item_id)];
+                        }
+                        MenuEvents::Back => { return vec![UIEvent::PreviousScreen]; }
+                    }
+                }
+                (InputEvent::UIBack, EventType::Pressed) => { return vec![UIEvent::PreviousScreen]; }
+                _ => { }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn render(&self, buffer : &mut RgbImage) {
+        self.page.draw(buffer);
+        self.menu.as_ref().unwrap().draw(buffer);
+    }
+}
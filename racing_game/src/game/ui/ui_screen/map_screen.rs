@@ -12,6 +12,7 @@ use super::UIScreen;
 
 pub struct MapScreen{
     page : UIPage,
+    font : Rc<Font>,
     map_center_pos : IVec2,
     map_size : IVec2,
 
@@ -56,6 +57,7 @@ impl MapScreen {
 
         MapScreen { 
             page : map_page, 
+            font,
 
             map_center_pos : IVec2::new(320, 180), 
             map_size : IVec2::zero(),
@@ -127,9 +129,17 @@ impl UIScreen for MapScreen {
             let image = UIImage::new(if city_mark.ending { self.ending_city_sprite.clone() } else { self.intermediate_city_sprite.clone() });
             self.page.add_control(Box::from(image), &ControlProperties { pivot : Pivot::Center, position : &city_mark.position + &map_left_bottom, binding : Binding::LeftBottom });
         }
+
+        // City numbers are used by job boards, cities where the cargo goes are marked with $.
+        for (id, city_mark) in self.city_marks.iter().enumerate() {
+            let has_cargo = game.player.cargo.iter().any(|cargo| cargo.destination_city_id == id);
+            let label = UIText::new(self.font.clone(), format!("{}{}", id, if has_cargo { "$" } else { "" }));
+            let position = &(&city_mark.position + &map_left_bottom) + &IVec2::new(8, 4);
+            self.page.add_control(Box::from(label), &ControlProperties { pivot : Pivot::LeftBottom, position, binding : Binding::LeftBottom });
+        }
     }   
 
-    fn update(&mut self, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent>{
+    fn update(&mut self, _game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent>{
         for (event, event_type) in input {
             match (event, event_type) {
                 (InputEvent::UIUp, EventType::Pressed) => { self.change_selected_city(&IVec2::new(0, 1)); }
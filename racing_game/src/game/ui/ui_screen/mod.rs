@@ -7,7 +7,6 @@ use crate::engine::common::IVec2;
 use crate::game::{Game, InputEvent, EventType};
 use crate::game::city_map::services::*;
 use super::UIEvent;
-use crate::engine::ui::font::*;
 
 mod map_screen;
 mod game_screen;
@@ -16,6 +15,11 @@ mod services_screen;
 mod service_select_screen;
 mod settings_screen;
 mod control_settings_screen;
+mod new_game_screen;
+mod garage_screen;
+mod rescue_screen;
+mod game_end_screen;
+mod inventory_screen;
 
 pub use map_screen::*;
 pub use game_screen::*;
@@ -24,6 +28,11 @@ pub use services_screen::*;
 pub use service_select_screen::*;
 pub use settings_screen::*;
 pub use control_settings_screen::*;
+pub use new_game_screen::*;
+pub use garage_screen::*;
+pub use rescue_screen::*;
+pub use game_end_screen::*;
+pub use inventory_screen::*;
 
 #[derive(Hash, Eq, PartialEq, Copy, Clone)]
 pub enum Screen{
@@ -33,17 +42,24 @@ pub enum Screen{
     Services,
     Settings,
     ControlSettings,
+    NewGame,
+    Garage,
+    Rescue,
+    Victory,
+    GameOver,
+    Inventory,
 
     GasStations,
     Hostels,
     RepairStations,
-    Shops
+    Shops,
+    JobBoards
 }
 
 pub fn create_all_screens(resolution : &IVec2) -> HashMap<Screen, Box<dyn UIScreen>>{
     let mut ui_screens = HashMap::<Screen, Box<dyn UIScreen>>::new();
 
-    let font = Font::new(Game::load_image_rgba("font.png"), IVec2::new(12, 12), String::from("ABCDEFGHIJKLMNOPQRSTUVWXYZ1234567890$%., "));
+    let font = Game::load_font();
     let font = Rc::from(font);
 
     let map_screen = Box::from(MapScreen::new(resolution, font.clone()));
@@ -52,11 +68,18 @@ pub fn create_all_screens(resolution : &IVec2) -> HashMap<Screen, Box<dyn UIScre
     let services_screen = Box::from(ServicesScreen::new(resolution, font.clone()));
     let settings_screen = Box::from(SettingsScreen::new(resolution, font.clone()));
     let control_settings_screen = Box::from(ControlSettingsScreen::new(resolution, font.clone()));
+    let new_game_screen = Box::from(NewGameScreen::new(resolution, font.clone()));
+    let garage_screen = Box::from(GarageScreen::new(resolution, font.clone()));
+    let rescue_screen = Box::from(RescueScreen::new(resolution, font.clone()));
+    let victory_screen = Box::from(GameEndScreen::new(resolution, font.clone(), "YOU REACHED THE FINISH"));
+    let game_over_screen = Box::from(GameEndScreen::new(resolution, font.clone(), "GAME OVER, NO MONEY FOR RESCUE"));
+    let inventory_screen = Box::from(InventoryScreen::new(resolution, font.clone()));
 
     let gas_stations_screen = Box::from(ServiceSelectScreen::<GasStation>::new(resolution, font.clone()));
     let hostels_screen = Box::from(ServiceSelectScreen::<Hostel>::new(resolution, font.clone()));
     let repair_stations_screen = Box::from(ServiceSelectScreen::<RepairStation>::new(resolution, font.clone()));
     let shops_screen = Box::from(ServiceSelectScreen::<Shop>::new(resolution, font.clone()));
+    let job_boards_screen = Box::from(ServiceSelectScreen::<JobBoard>::new(resolution, font.clone()));
 
     ui_screens.insert(Screen::Map, map_screen);
     ui_screens.insert(Screen::Game, game_screen);
@@ -64,17 +87,26 @@ pub fn create_all_screens(resolution : &IVec2) -> HashMap<Screen, Box<dyn UIScre
     ui_screens.insert(Screen::Services, services_screen);
     ui_screens.insert(Screen::Settings, settings_screen);
     ui_screens.insert(Screen::ControlSettings, control_settings_screen);
+    ui_screens.insert(Screen::NewGame, new_game_screen);
+    ui_screens.insert(Screen::Garage, garage_screen);
+    ui_screens.insert(Screen::Rescue, rescue_screen);
+    ui_screens.insert(Screen::Victory, victory_screen);
+    ui_screens.insert(Screen::GameOver, game_over_screen);
+    ui_screens.insert(Screen::Inventory, inventory_screen);
 
     ui_screens.insert(Screen::GasStations, gas_stations_screen);
     ui_screens.insert(Screen::Hostels, hostels_screen);
     ui_screens.insert(Screen::RepairStations, repair_stations_screen);
     ui_screens.insert(Screen::Shops, shops_screen);
+    ui_screens.insert(Screen::JobBoards, job_boards_screen);
 
     ui_screens
 }
 
 pub trait UIScreen {
-    fn update(&mut self, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent>;
+    fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent>;
     fn init(&mut self, game : &Game);
     fn render(&self, buffer : &mut RgbImage);
+    // Result of the action the screen asked for, screens without such actions ignore it.
+    fn show_message(&mut self, _message : &str) { }
 }
\ No newline at end of file
@@ -0,0 +1,228 @@
+use std::rc::Rc;
+
+use image::{RgbImage, Rgb};
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::engine::common::IVec2;
+use crate::engine::ui::font::*;
+use crate::engine::ui::*;
+use crate::engine::window::Key;
+use crate::game::new_game::*;
+use crate::game::{Game, InputEvent, EventType};
+use crate::game::ui::UIEvent;
+
+use super::UIScreen;
+
+#[derive(Copy, Clone, PartialEq)]
+enum MenuEvents {
+    Seed,
+    RandomSeed,
+    Cities,
+    MapSize,
+
+    Start,
+    Load
+}
+
+const MAP_SIZE_STEP : isize = 50;
+
+pub struct NewGameScreen {
+    page : UIPage,
+    menu : UISelector<MenuEvents>,
+    font : Rc<Font>,
+    resolution : IVec2,
+    parameters : Option<NewGameParameters>,
+    seed_code : String,
+    editing_seed : bool,
+    // Random seeds are taken from the seeded rng, so recorded inputs replay the same maps.
+    rng : Option<StdRng>
+}
+
+impl NewGameScreen {
+    pub fn new(resolution : &IVec2, font : Rc<Font>) -> NewGameScreen {
+        let pointer_image = Game::load_image_rgba("ui/pointer.png");
+
+        let menu_items : Vec<UISelectorItem<MenuEvents>> = vec![
+            UISelectorItem::new(
+                Box::from(UIText::new(font.clone(), String::from("SEED"))),
+                ControlProperties { pivot : Pivot::RightBottom, binding : Binding::Center, position : IVec2::new(-10, 60) },
+                MenuEvents::Seed
+            ),
+            UISelectorItem::new(
+                Box::from(UIText::new(font.clone(), String::from("RANDOM SEED"))),
+                ControlProperties { pivot : Pivot::RightBottom, binding : Binding::Center, position : IVec2::new(-10, 40) },
+                MenuEvents::RandomSeed
+            ),
+            UISelectorItem::new(
+                Box::from(UIText::new(font.clone(), String::from("CITIES"))),
+                ControlProperties { pivot : Pivot::RightBottom, binding : Binding::Center, position : IVec2::new(-10, 20) },
+                MenuEvents::Cities
+            ),
+            UISelectorItem::new(
+                Box::from(UIText::new(font.clone(), String::from("MAP SIZE"))),
+                ControlProperties { pivot : Pivot::RightBottom, binding : Binding::Center, position : IVec2::new(-10, 0) },
+                MenuEvents::MapSize
+            ),
+            UISelectorItem::new(
+                Box::from(UIText::new(font.clone(), String::from("START"))),
+                ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, -100) },
+                MenuEvents::Start
+            ),
+            UISelectorItem::new(
+                Box::from(UIText::new(font.clone(), String::from("LOAD GAME"))),
+                ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, -120) },
+                MenuEvents::Load
+            )
+        ];
+
+        let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
+        let menu = UISelector::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, resolution.clone(), None);
+
+        let page = UIPage::new(*resolution, Some(Rgb([0, 0, 0])));
+
+        NewGameScreen { page, menu, font, resolution : *resolution, parameters : None, seed_code : String::new(), editing_seed : false, rng : None }
+    }
+
+    fn refresh_values(&mut self) {
+        self.page.clear_controls();
+        let parameters = self.parameters.as_ref().unwrap();
+
+        let seed = if self.editing_seed { format!("{}.", self.seed_code) } else { self.seed_code.clone() };
+        let values = vec![
+            seed,
+            format!("{}", parameters.generation.city_count),
+            format!("{}", parameters.generation.size.x)
+        ];
+        let positions = vec![60, 20, 0];
+        for (value, position) in values.into_iter().zip(positions) {
+            let label = Box::from(UIText::new(self.font.clone(), value));
+            self.page.add_control(label, &ControlProperties { pivot : Pivot::LeftBottom, binding : Binding::Center, position : IVec2::new(10, position) });
+        }
+    }
+
+    fn change_value(&mut self, direction : isize) {
+        let parameters = self.parameters.as_mut().unwrap();
+        match self.menu.select_current() {
+            MenuEvents::Cities => {
+                let city_count = parameters.generation.city_count as isize + direction;
+                let max_city_count = parameters.max_city_count() as isize;
+                parameters.generation.city_count = city_count.clamp(NewGameParameters::MIN_CITY_COUNT as isize, max_city_count) as u32;
+            }
+            MenuEvents::MapSize => {
+                let size = (parameters.generation.size.x + direction * MAP_SIZE_STEP).clamp(NewGameParameters::MIN_MAP_SIZE, NewGameParameters::MAX_MAP_SIZE);
+                parameters.generation.size = IVec2::new(size, size);
+                // Smaller map fits less cities.
+                parameters.generation.city_count = parameters.generation.city_count.min(parameters.max_city_count());
+            }
+            _ => { return; }
+        }
+        self.refresh_values();
+    }
+
+    fn finish_seed_editing(&mut self) {
+        self.editing_seed = false;
+        let parameters = self.parameters.as_mut().unwrap();
+        match WorldSeed::from_code(&self.seed_code) {
+            Ok(seed) => { parameters.seed = seed; }
+            // Keep the previous seed if the code is empty.
+            Err(_) => { self.seed_code = parameters.seed.to_code(); }
+        }
+        self.refresh_values();
+    }
+
+    fn seed_symbol(key : Key) -> Option<char> {
+        let code = key as i32;
+        let is_letter = code >= Key::A as i32 && code <= Key::Z as i32;
+        let is_digit = code >= Key::Num0 as i32 && code <= Key::Num9 as i32;
+        if is_letter || is_digit { Some(code as u8 as char) } else { None }
+    }
+}
+
+impl UIScreen for NewGameScreen {
+    fn init(&mut self, game : &Game) {
+        let parameters = game.get_new_game_parameters();
+        if self.rng.is_none() {
+            self.rng = Some(StdRng::seed_from_u64(parameters.seed.0));
+        }
+        self.seed_code = parameters.seed.to_code();
+        self.parameters = Some(parameters);
+        self.editing_seed = false;
+
+        self.refresh_values();
+    }
+
+    fn update(&mut self, _game : &Game, input : &Vec<(InputEvent, EventType)>, _delta_time : f32) -> Vec<UIEvent> {
+        for (event, event_type) in input {
+            if self.editing_seed {
+                match (event, event_type) {
+                    (InputEvent::UISelect, EventType::Pressed) => { self.finish_seed_editing(); }
+                    (InputEvent::AnyKey(Key::Backspace), EventType::Pressed) => {
+                        self.seed_code.pop();
+                        self.refresh_values();
+                    }
+                    (InputEvent::AnyKey(key), EventType::Pressed) => {
+                        if let Some(symbol) = Self::seed_symbol(*key) {
+                            if self.seed_code.len() < MAX_SEED_CODE_LENGTH {
+                                self.seed_code.push(symbol);
+                                self.refresh_values();
+                            }
+                        }
+                    }
+                    _ => { }
+                }
+                continue;
+            }
+
+            match (event, event_type) {
+                (InputEvent::UIDown, EventType::Pressed) => { self.menu.select_next_in_direction(&IVec2::new(0, -1)); }
+                (InputEvent::UIUp, EventType::Pressed) => { self.menu.select_next_in_direction(&IVec2::new(0, 1)); }
+                (InputEvent::UILeft, EventType::Pressed) => { self.change_value(-1); }
+                (InputEvent::UIRight, EventType::Pressed) => { self.change_value(1); }
+                (InputEvent::UISelect, EventType::Pressed) => {
+                    match self.menu.select_current() {
+                        MenuEvents::Seed => {
+                            self.editing_seed = true;
+                            self.seed_code.clear();
+                            self.refresh_values();
+                        }
+                        MenuEvents::RandomSeed => {
+                            let seed = WorldSeed::random_from(self.rng.as_mut().unwrap());
+                            self.parameters.as_mut().unwrap().seed = seed;
+                            self.seed_code = seed.to_code();
+                            self.refresh_values();
+                        }
+                        MenuEvents::Start => {
+                            let parameters = self.parameters.clone().unwrap();
+                            match parameters.validate() {
+                                Ok(_) => { return vec![UIEvent::NewGame(parameters)]; }
+                                Err(e) => { self.show_message(&e); }
+                            }
+                        }
+                        MenuEvents::Load => { return vec![UIEvent::LoadGame]; }
+                        _ => { }
+                    }
+                }
+                _ => { }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn render(&self, buffer : &mut RgbImage) {
+        self.page.draw(buffer);
+        self.menu.draw(buffer);
+    }
+
+    // Invalid parameters and load errors are explained under the menu until a value is changed.
+    fn show_message(&mut self, message : &str) {
+        self.refresh_values();
+        let lines = self.font.wrap(&self.font.to_printable(message), self.resolution.x as u32 - 40);
+        for (i, line) in lines.into_iter().enumerate() {
+            self.page.add_control(
+                Box::from(UIText::new(self.font.clone(), line)),
+                &ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, -150 - 20 * i as isize) }
+            );
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::rc::Rc;
+
+use image::{RgbImage, Rgb};
+
+use crate::engine::common::IVec2;
+use crate::engine::ui::font::*;
+use crate::engine::ui::*;
+use crate::game::*;
+use crate::game::ride::{RescueOption, RESCUE_GAS_AMOUNT, RESCUE_GAS_COST};
+use crate::game::ui::UIEvent;
+
+use super::UIScreen;
+
+pub struct RescueScreen {
+    page : UIPage,
+    message_page : UIPage,
+    menu : Option<UISelector<RescueOption>>,
+    font : Rc<Font>,
+    resolution : IVec2
+}
+
+impl RescueScreen {
+    pub fn new(resolution : &IVec2, font : Rc<Font>) -> RescueScreen {
+        let page = UIPage::new(*resolution, Some(Rgb([0, 0, 0])));
+        RescueScreen { page, message_page : UIPage::new(*resolution, None), menu : None, font, resolution : *resolution }
+    }
+}
+
+impl UIScreen for RescueScreen {
+    // Costs depend on the distance left, so options are rebuilt every time.
+    fn init(&mut self, game : &Game) {
+        // Seized engine can't be helped with gas.
+        let seized = game.ride.car.is_engine_seized();
+        let title = if seized { "ENGINE SEIZED" } else { "OUT OF GAS" };
+        self.page.clear_controls();
+        self.message_page.clear_controls();
+        self.page.add_control(
+            Box::from(UIText::new(self.font.clone(), String::from(title))),
+            &ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, 60) }
+        );
+
+        let mut options = vec![(format!("TOW TO CITY FOR ${}", game.get_tow_cost().ceil()), RescueOption::Tow)];
+        if !seized { options.push((format!("BUY {} L. FOR ${}", RESCUE_GAS_AMOUNT, RESCUE_GAS_COST), RescueOption::Refuel)); }
+        if !seized && game.player.inventory.find_jerrycan().is_some() { options.push((String::from("USE JERRYCAN"), RescueOption::UseJerrycan)); }
+
+        let menu_items = options.into_iter().enumerate()
+        .map(|(i, (text, option))| UISelectorItem::new(
+            Box::from(UIText::new(self.font.clone(), text)),
+            ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, -20 * i as isize) },
+            option
+        ))
+        .collect();
+
+        let pointer_image = Game::load_image_rgba("ui/pointer.png");
+        let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
+        self.menu = Some(UISelector::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution, None));
+    }
+
+    fn update(&mut self, _game : &Game, input : &Vec<(InputEvent, EventType)>, _delta_time : f32) -> Vec<UIEvent> {
+        for (event, event_type) in input {
+            match (event, event_type) {
+                (InputEvent::UIDown, EventType::Pressed) => { self.menu.as_mut().unwrap().select_next_in_direction(&IVec2::new(0, -1)); }
+                (InputEvent::UIUp, EventType::Pressed) => { self.menu.as_mut().unwrap().select_next_in_direction(&IVec2::new(0, 1)); }
+                (InputEvent::UISelect, EventType::Pressed) => { return vec![UIEvent::Rescue(self.menu.as_mut().unwrap().select_current())]; }
+                _ => { }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn render(&self, buffer : &mut RgbImage) {
+        self.page.draw(buffer);
+        self.message_page.draw(buffer);
+        self.menu.as_ref().unwrap().draw(buffer);
+    }
+
+    // Player stays on the screen when the chosen help can't be paid for.
+    fn show_message(&mut self, message : &str) {
+        self.message_page.clear_controls();
+        self.message_page.add_control(
+            Box::from(UIText::new(self.font.clone(), self.font.to_printable(message))),
+            &ControlProperties { pivot : Pivot::Center, binding : Binding::Center, position : IVec2::new(0, -80) }
+        );
+    }
+}
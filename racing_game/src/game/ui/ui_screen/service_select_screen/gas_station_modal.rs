@@ -12,6 +12,8 @@ use super::*;
 
 pub struct GasStationModal{
     buy_gas_amount : u32,
+    // Left and right switch between gas and oil.
+    buy_oil : bool,
     selected_service : ServiceId,
     font : Rc<Font>,
     pub modal : ModalPage
@@ -22,6 +24,7 @@ impl GasStationModal {
         let modal = ModalPage::new(IVec2::new(100, 100), IVec2::new(200, 100), Some(Rgb([150, 150, 150])));
         GasStationModal { 
             buy_gas_amount : 0,
+            buy_oil : false,
             selected_service : ServiceId(0),
             font,
             modal
@@ -30,11 +33,21 @@ impl GasStationModal {
 }
 
 impl ServiceModal for GasStationModal {
-    fn unfold(&mut self, game : &Game) { self.modal.start_anim_unfold(1000.0); }
+    fn unfold(&mut self, game : &Game) {
+        self.buy_oil = false;
+        self.modal.start_anim_unfold(1000.0);
+    }
     
     fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<ServiceModalEvent> {
         let gas_station = game.city_map.get_service::<GasStation>(self.selected_service);
-        let player_money = game.player.money;
+        let price_level = game.city_map.get_price_level();
+        // Purchase might have left less money or room in the tank.
+        let max_gas_amount = if self.buy_oil {
+            gas_station.get_max_oil_amount(&game.player, price_level)
+        } else {
+            gas_station.get_max_gas_amount(&game.player, price_level)
+        };
+        self.buy_gas_amount = Math::min(self.buy_gas_amount, max_gas_amount);
 
         for (event, event_type) in input {
             match (event, event_type) {
@@ -44,14 +57,15 @@ impl ServiceModal for GasStationModal {
                     } 
                 }
                 (InputEvent::UIUp, EventType::Pressed) => { 
-                    self.buy_gas_amount = Math::min(gas_station.get_max_gas_amount(player_money), self.buy_gas_amount + 1);
+                    self.buy_gas_amount = Math::min(max_gas_amount, self.buy_gas_amount + 1);
+                }
+                (InputEvent::UILeft, EventType::Pressed) | (InputEvent::UIRight, EventType::Pressed) => {
+                    self.buy_oil = !self.buy_oil;
+                    self.buy_gas_amount = 0;
                 }
                 (InputEvent::UISelect, EventType::Pressed) => { 
-                    return vec![
-                        ServiceModalEvent::UIEvent(
-                            UIEvent::ServiceAction(self.selected_service, ServiceAction::BuyGas(self.buy_gas_amount))
-                        )
-                    ]; 
+                    let action = if self.buy_oil { ServiceAction::BuyOil(self.buy_gas_amount) } else { ServiceAction::BuyGas(self.buy_gas_amount) };
+                    return vec![ServiceModalEvent::UIEvent(UIEvent::ServiceAction(self.selected_service, action))]; 
                 }
                 (InputEvent::UIBack, EventType::Pressed) => { 
                     self.modal.start_anim_fold(1000.0);
@@ -63,10 +77,17 @@ impl ServiceModal for GasStationModal {
         }
 
         self.modal.clear_controls();
-        let cost = gas_station.get_cost(self.buy_gas_amount);
+        let (product, cost) = if self.buy_oil {
+            ("OIL", gas_station.get_oil_total_cost(self.buy_gas_amount, price_level))
+        } else {
+            ("GAS", gas_station.get_cost(self.buy_gas_amount, price_level))
+        };
+        let product_text = UIText::new(self.font.clone(), String::from(product));
+        let product_props = ControlProperties { binding : Binding::Center, pivot : Pivot::Center, position : IVec2::new(0, 15) };
+        self.modal.add_control(Box::from(product_text), product_props);
         let buy_string = self.buy_gas_amount.to_string() + "L. FOR " + cost.to_string().as_ref() + "$";
         let text = UIText::new(self.font.clone(), buy_string);
-        let text_props = ControlProperties { binding : Binding::Center, pivot : Pivot::Center, position : IVec2::zero() };
+        let text_props = ControlProperties { binding : Binding::Center, pivot : Pivot::Center, position : IVec2::new(0, -15) };
         self.modal.add_control(Box::from(text), text_props);
         self.modal.update(delta_time);
 
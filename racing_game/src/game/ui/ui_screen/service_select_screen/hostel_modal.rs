@@ -12,33 +12,35 @@ pub struct HostelModal {
     selected_service : ServiceId,
     font : Rc<Font>,
     resolution : IVec2,
-    modal : ModalPage
+    modal : ModalPage,
+    // Prices shown in the list are outdated after a stay.
+    refresh_options : bool
 }
 
 impl HostelModal {
     pub fn new(resolution : &IVec2, font : Rc<Font>) -> HostelModal {
-        let modal = ModalPage::new(IVec2::new(100, 100), IVec2::new(200, 100), Some(Rgb([150, 150, 150])));
+        let modal = ModalPage::new(IVec2::new(160, 90), IVec2::new(320, 110), Some(Rgb([150, 150, 150])));
         HostelModal { 
             selected_service : ServiceId(0),
             font,
             modal,
-            resolution : resolution.clone()
+            resolution : resolution.clone(),
+            refresh_options : false
         }
     }
-}
 
-#[derive(Clone)]
-struct OptionSelect(usize);
+    fn get_option_selector(&mut self) -> &mut UISelector<OptionSelect> {
+        unsafe { &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<OptionSelect>) }
+    }
 
-impl ServiceModal for HostelModal {
-    fn unfold(&mut self, game : &Game) { 
+    fn build_options(&mut self, game : &Game, selected_item : usize) {
         let pointer_image = Game::load_image_rgba("ui/pointer.png");
         let mut menu_items = Vec::new();
 
         let hostel = game.city_map.get_service::<Hostel>(self.selected_service);
         for i in 0..hostel.options.len() {
             let time = hostel.options[i].time.clone();
-            let cost = hostel.options[i].cost;
+            let cost = hostel.get_rest_cost(i as u32, game.city_map.get_price_level());
             menu_items.push(UISelectorItem::new(
                 Box::from(UIText::new(self.font.clone(), format!("REST {}H. {}M. FOR {}$", time.hr, time.min, cost))),
                 ControlProperties::new(IVec2::new(0, -(i as isize) * 20), Pivot::Center, Binding::Center),
@@ -47,36 +49,41 @@ impl ServiceModal for HostelModal {
         }
 
         let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
-        let option_selector = UISelector::<OptionSelect>::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution.clone(), None);
+        let mut option_selector = UISelector::<OptionSelect>::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution.clone(), None);
+        option_selector.set_selected_item(selected_item);
         self.modal.clear_controls();
         self.modal.add_control(Box::from(option_selector), ControlProperties { position : IVec2::zero(), pivot : Pivot::LeftBottom, binding : Binding::LeftBottom });
+    }
+}
 
+#[derive(Clone)]
+struct OptionSelect(usize);
+
+impl ServiceModal for HostelModal {
+    fn unfold(&mut self, game : &Game) { 
+        self.refresh_options = false;
+        self.build_options(game, 0);
         self.modal.start_anim_unfold(1000.0); 
     }
     
     fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<ServiceModalEvent> {
-        let hostel = game.city_map.get_service::<Hostel>(self.selected_service);
-        let player_money = game.player.money;
+        if self.refresh_options {
+            self.refresh_options = false;
+            let selected_item = self.get_option_selector().get_selected_item();
+            self.build_options(game, selected_item);
+        }
 
         for (event, event_type) in input {
             match (event, event_type) {
                 (InputEvent::UIDown, EventType::Pressed) => { 
-                    unsafe {
-                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<OptionSelect>);
-                        ui_select.select_next_in_direction(&IVec2::new(0, -1));
-                    }
+                    self.get_option_selector().select_next_in_direction(&IVec2::new(0, -1));
                 }
                 (InputEvent::UIUp, EventType::Pressed) => { 
-                    unsafe {
-                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<OptionSelect>);
-                        ui_select.select_next_in_direction(&IVec2::new(0, 1));
-                    }
+                    self.get_option_selector().select_next_in_direction(&IVec2::new(0, 1));
                 }
                 (InputEvent::UISelect, EventType::Pressed) => { 
-                    let selected = unsafe {
-                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<OptionSelect>);
-                        ui_select.select_current()
-                    };
+                    let selected = self.get_option_selector().select_current();
+                    self.refresh_options = true;
 
                     return vec![
                         ServiceModalEvent::UIEvent(
@@ -0,0 +1,119 @@
+use std::rc::Rc;
+
+use crate::engine::common::IVec2;
+use crate::engine::ui::font::*;
+use crate::engine::ui::*;
+use crate::game::*;
+use crate::game::city_map::services::*;
+
+use super::*;
+
+pub struct JobBoardModal {
+    selected_service : ServiceId,
+    font : Rc<Font>,
+    resolution : IVec2,
+    modal : ModalPage,
+    // Contract ids shown in the selector, empty when there is nothing to take.
+    offers : Vec<usize>
+}
+
+impl JobBoardModal {
+    pub fn new(resolution : &IVec2, font : Rc<Font>) -> JobBoardModal {
+        let modal = ModalPage::new(IVec2::new(40, 100), IVec2::new(560, 120), Some(Rgb([150, 150, 150])));
+        JobBoardModal {
+            selected_service : ServiceId(0),
+            font,
+            modal,
+            resolution : resolution.clone(),
+            offers : Vec::new()
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ContractSelect(usize);
+
+impl ServiceModal for JobBoardModal {
+    fn unfold(&mut self, game : &Game) {
+        self.modal.clear_controls();
+
+        let job_board = game.city_map.get_service::<JobBoard>(self.selected_service);
+        let now = game.get_clock().get_minutes();
+        self.offers = if game.player.cargo.len() < MAX_CARGO_COUNT { job_board.get_offers(game.city_map.current_city_id) } else { Vec::new() };
+
+        if self.offers.is_empty() {
+            let text = if game.player.cargo.len() < MAX_CARGO_COUNT { "NO CONTRACTS" } else { "CAR IS FULL OF CARGO" };
+            let text = UIText::new(self.font.clone(), String::from(text));
+            self.modal.add_control(Box::from(text), ControlProperties { position : IVec2::zero(), pivot : Pivot::Center, binding : Binding::Center });
+        } else {
+            let mut menu_items = Vec::new();
+            for (i, &contract_id) in self.offers.iter().enumerate() {
+                let cargo = game.city_map.get_contract_offer(self.selected_service, contract_id, now);
+                let hours = ((cargo.deadline - now) / 60.0).floor();
+                let fragile = if cargo.fragility > FRAGILE_LEVEL { " FRAGILE" } else { "" };
+                menu_items.push(UISelectorItem::new(
+                    Box::from(UIText::new(self.font.clone(), format!("TO CITY {} IN {}H. FOR {}${}", cargo.destination_city_id, hours, cargo.reward, fragile))),
+                    ControlProperties::new(IVec2::new(0, 30 - (i as isize) * 20), Pivot::Center, Binding::Center),
+                    ContractSelect(contract_id)
+                ));
+            }
+
+            let pointer_image = Game::load_image_rgba("ui/pointer.png");
+            let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
+            let contract_selector = UISelector::<ContractSelect>::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution.clone(), None);
+            self.modal.add_control(Box::from(contract_selector), ControlProperties { position : IVec2::zero(), pivot : Pivot::LeftBottom, binding : Binding::LeftBottom });
+        }
+
+        self.modal.start_anim_unfold(1000.0);
+    }
+
+    fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<ServiceModalEvent> {
+        for (event, event_type) in input {
+            match (event, event_type) {
+                (InputEvent::UIDown, EventType::Pressed) if !self.offers.is_empty() => {
+                    unsafe {
+                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<ContractSelect>);
+                        ui_select.select_next_in_direction(&IVec2::new(0, -1));
+                    }
+                }
+                (InputEvent::UIUp, EventType::Pressed) if !self.offers.is_empty() => {
+                    unsafe {
+                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<ContractSelect>);
+                        ui_select.select_next_in_direction(&IVec2::new(0, 1));
+                    }
+                }
+                (InputEvent::UISelect, EventType::Pressed) if !self.offers.is_empty() => {
+                    let selected = unsafe {
+                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<ContractSelect>);
+                        ui_select.select_current()
+                    };
+                    let cargo = game.city_map.get_contract_offer(self.selected_service, selected.0, game.get_clock().get_minutes());
+
+                    // Taken contract leaves the board, so the modal is closed to be rebuilt next time.
+                    self.modal.start_anim_fold(1000.0);
+                    return vec![
+                        ServiceModalEvent::Close,
+                        ServiceModalEvent::UIEvent(
+                            UIEvent::ServiceAction(self.selected_service, ServiceAction::TakeContract(selected.0, cargo))
+                        )
+                    ];
+                }
+                (InputEvent::UIBack, EventType::Pressed) => {
+                    self.modal.start_anim_fold(1000.0);
+                    return vec![ServiceModalEvent::Close];
+                }
+                _ => { }
+            }
+        }
+
+        self.modal.update(delta_time);
+
+        Vec::new()
+    }
+
+    fn select_service(&mut self, id: ServiceId) { self.selected_service = id; }
+
+    fn is_busy(&self) -> bool { self.modal.anim_state != ModalAnim::Void }
+
+    fn draw(&self, buffer : &mut RgbImage) { self.modal.draw(buffer); }
+}
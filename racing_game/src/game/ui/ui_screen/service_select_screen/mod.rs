@@ -16,11 +16,13 @@ mod gas_station_modal;
 mod hostel_modal;
 mod shop_modal;
 mod repair_station_modal;
+mod job_board_modal;
 
 use gas_station_modal::*;
 use hostel_modal::*;
 use shop_modal::*;
 use repair_station_modal::*;
+use job_board_modal::*;
 
 pub trait ServiceModal { 
     fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<ServiceModalEvent>;
@@ -52,8 +54,10 @@ enum State{
 pub struct ServiceSelectScreen<T> where T : Service{
     _type : std::marker::PhantomData<T>,
     menu : Option<UISelector<MenuEvents>>,
+    // Names and prices shown next to the logos.
+    labels : UIPage,
+    message_page : UIPage,
     service_modal : Box<dyn ServiceModal>,
-    game : Option<Rc<Game>>,
     state : State,
     font : Rc<Font>,
     resolution : IVec2
@@ -71,30 +75,49 @@ impl<T> ServiceSelectScreen<T> where T : Service {
             Box::<dyn ServiceModal>::from(Box::from(RepairStationModal::new(resolution, font.clone())))
         } else if service_id == TypeId::of::<Shop>() {
             Box::<dyn ServiceModal>::from(Box::from(ShopModal::new(resolution, font.clone())))
+        } else if service_id == TypeId::of::<JobBoard>() {
+            Box::<dyn ServiceModal>::from(Box::from(JobBoardModal::new(resolution, font.clone())))
         } else { panic!("incorrect service type") };
 
         ServiceSelectScreen::<T> { 
             _type : std::marker::PhantomData::<T>,
             menu : None,
+            labels : UIPage::new(resolution.clone(), None),
+            message_page : UIPage::new(resolution.clone(), None),
             service_modal, 
             state : State::SelectingService,
-            game : None,
             font,
             resolution : resolution.clone()
         }
     }
+
+    fn build_labels(&mut self, game : &Game) where T : 'static {
+        self.labels.clear_controls();
+        let service_ids = game.city_map.get_current_city_services_subset().get_of_type::<T>();
+        for (i, id) in service_ids.into_iter().enumerate() {
+            let service = game.city_map.get_service::<T>(id);
+            let y = -24 - 20 * i as isize;
+            self.labels.add_control(
+                Box::from(UIText::new(self.font.clone(), String::from(service.get_name()))),
+                &ControlProperties { pivot : Pivot::LeftTop, position : IVec2::new(60, y), binding : Binding::LeftTop }
+            );
+            self.labels.add_control(
+                Box::from(UIText::new(self.font.clone(), service.get_price_summary(game.city_map.get_price_level()))),
+                &ControlProperties { pivot : Pivot::LeftTop, position : IVec2::new(280, y), binding : Binding::LeftTop }
+            );
+        }
+    }
 }
 
 impl<T> UIScreen for ServiceSelectScreen<T> where T : Service + 'static {
     fn init(&mut self, game : &Game) {
-        unsafe { self.game = Some(Rc::from_raw(game as *const Game)); }
         let service_ids = game.city_map.get_current_city_services_subset().get_of_type::<T>();
 
         let mut menu_items = Vec::new();
 
         let mut i = 0;
         for id in service_ids {
-            let service = self.game.as_ref().unwrap().city_map.get_service::<T>(id);
+            let service = game.city_map.get_service::<T>(id);
             let logo = UIImage::new(service.get_logo());
             let station_item = UISelectorItem::new(
                 Box::from(logo), 
@@ -127,12 +150,14 @@ impl<T> UIScreen for ServiceSelectScreen<T> where T : Service + 'static {
         let pointer_image = Game::load_image_rgba("ui/pointer.png");
         let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
         self.menu = Some(UISelector::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution.clone(), None));
+        self.build_labels(game);
+        self.message_page.clear_controls();
     }
 
-    fn update(&mut self, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
+    fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
         let void_input = Vec::new();
         let service_events = self.service_modal.as_mut().update(
-            self.game.as_ref().unwrap(), 
+            game, 
             if self.state == State::ActionsInService { input } else { &void_input }, 
             delta_time
         );
@@ -148,8 +173,9 @@ impl<T> UIScreen for ServiceSelectScreen<T> where T : Service + 'static {
                             let menu_event = self.menu.as_mut().unwrap().select_current();
                             match menu_event {
                                 MenuEvents::Select(id) => { 
+                                    self.message_page.clear_controls();
                                     self.service_modal.select_service(id);
-                                    self.service_modal.unfold(self.game.as_ref().unwrap());
+                                    self.service_modal.unfold(game);
                                     self.state = State::OpeningModalWindow;
                                 },
                                 MenuEvents::Back => { 
@@ -178,6 +204,8 @@ impl<T> UIScreen for ServiceSelectScreen<T> where T : Service + 'static {
             State::ClosingModalWindow => {
                 if !self.service_modal.is_busy() { 
                     self.state = State::SelectingService; 
+                    // Purchases change prices.
+                    self.build_labels(game);
                 }
             }
 
@@ -189,6 +217,20 @@ impl<T> UIScreen for ServiceSelectScreen<T> where T : Service + 'static {
 
     fn render(&self, buffer : &mut RgbImage) {
         self.menu.as_ref().unwrap().draw(buffer);
+        self.labels.draw(buffer);
         self.service_modal.draw(buffer);
+        self.message_page.draw(buffer);
+    }
+
+    // Refused service actions are explained above the back button.
+    fn show_message(&mut self, message : &str) {
+        self.message_page.clear_controls();
+        let lines = self.font.wrap(&self.font.to_printable(message), self.resolution.x as u32 - 40);
+        for (i, line) in lines.into_iter().enumerate() {
+            self.message_page.add_control(
+                Box::from(UIText::new(self.font.clone(), line)),
+                &ControlProperties { pivot : Pivot::LeftBottom, binding : Binding::LeftBottom, position : IVec2::new(20, 60 - 20 * i as isize) }
+            );
+        }
     }
 }
\ No newline at end of file
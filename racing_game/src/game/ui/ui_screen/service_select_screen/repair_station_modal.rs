@@ -1,5 +1,4 @@
 use std::rc::Rc;
-use std::collections::HashMap;
 
 use crate::engine::common::{IVec2, Math};
 use crate::engine::ui::font::*;
@@ -10,142 +9,178 @@ use crate::game::ride::car::*;
 
 use super::*;
 
+// Repair amount changes by this many percents.
+const REPAIR_STEP : f32 = 10.0;
+
 pub struct RepairStationModal {
     selected_service : ServiceId,
     font : Rc<Font>,
     modal : ModalPage,
     diagnosed_modal : ModalPage,
+    repair_modal : ModalPage,
     diagnosed : bool,
-    unfold_diagnosed : bool
+    unfold_diagnosed : bool,
+    // Conditions shown in the diagnosis are outdated after a repair.
+    refresh_diagnosis : bool,
+    repair_percent : f32
 }
 
 impl RepairStationModal {
     pub fn new(resolution : &IVec2, font : Rc<Font>) -> RepairStationModal {
         let modal = ModalPage::new(IVec2::new(100, 100), IVec2::new(200, 100), Some(Rgb([150, 150, 150])));
-        let diagnosed_modal = ModalPage::new(IVec2::new(100, 120), IVec2::new(200, 100), Some(Rgb([100, 100, 100])));
-        RepairStationModal { 
+        let diagnosed_modal = ModalPage::new(IVec2::new(120, 120), IVec2::new(400, 140), Some(Rgb([100, 100, 100])));
+        let repair_modal = ModalPage::new(IVec2::new(120, 70), IVec2::new(400, 40), Some(Rgb([150, 150, 150])));
+        RepairStationModal {
             selected_service : ServiceId(0),
             font,
             modal,
             diagnosed_modal,
+            repair_modal,
             diagnosed : false,
-            unfold_diagnosed : false
+            unfold_diagnosed : false,
+            refresh_diagnosis : false,
+            repair_percent : 0.0
         }
     }
-}
-
-impl ServiceModal for RepairStationModal {
-    fn unfold(&mut self, game : &Game) { 
-        self.diagnosed = false;
-
-        let repair_station = game.city_map.get_service::<RepairStation>(self.selected_service);
-
-        let diagnose_cost = UIText::new(self.font.clone(), format!("DIAGNOSE FOR {}$", repair_station.get_diagnosis_cost()));
-        self.modal.clear_controls();
-        self.modal.add_control(Box::from(diagnose_cost), ControlProperties { position : IVec2::zero(), pivot : Pivot::Center, binding : Binding::Center });
-        
-        let mut fix_cost_items : Vec<UISelectorItem<CarSystem>> = Vec::new();
-        let mut i = 0;
-        for (system, fix_cost) in repair_station.repair_costs.iter() {
-            let system_name = match system {
-                CarSystem::Wheels => { "WHEELS" }
-                CarSystem::Transmission => { "TRANSMISSION" }
-                CarSystem::Chase => { "CHASE" }
-                CarSystem::Engine => { "ENGINE" }
-                CarSystem::Brake => { "BRAKE" }
-                CarSystem::Starter => { "STARTER" }
-            };
 
-            let fix_cost_str = format!("FIX {} FOR {}$", system_name, fix_cost);
-            let fix_cost_control = Box::from(UIText::new(self.font.clone(), fix_cost_str));
+    fn get_system_selector(&mut self) -> &mut UISelector<CarSystem> {
+        unsafe { &mut *(self.diagnosed_modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<CarSystem>) }
+    }
 
-            fix_cost_items.push(UISelectorItem::new(
-                fix_cost_control, 
-                ControlProperties { position : IVec2::new(0, 0 - i * 20), pivot : Pivot::Center, binding : Binding::Center },
+    // Actual condition of every car system, selection is kept when the list is rebuilt.
+    fn build_diagnosis(&mut self, car : &Car, selected_item : usize) {
+        let mut condition_items : Vec<UISelectorItem<CarSystem>> = Vec::new();
+        for (i, system) in CarSystem::all().iter().enumerate() {
+            let condition_str = format!("{} {}%", system.get_name(), car.get_condition(*system).0.floor());
+            condition_items.push(UISelectorItem::new(
+                Box::from(UIText::new(self.font.clone(), condition_str)),
+                ControlProperties { position : IVec2::new(0, 60 - i as isize * 20), pivot : Pivot::Center, binding : Binding::Center },
                 *system
             ));
-            i += 1;
         }
 
         let pointer_image = Game::load_image_rgba("ui/pointer.png");
         let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
-        let system_fix_selector = UISelector::new(fix_cost_items, SelectionType::Vertical, pointer_image, pointer_offset, SCREEN_RESOLUTION, None);
+        let mut system_selector = UISelector::new(condition_items, SelectionType::Vertical, pointer_image, pointer_offset, SCREEN_RESOLUTION, None);
+        system_selector.set_selected_item(selected_item);
 
         self.diagnosed_modal.clear_controls();
         self.diagnosed_modal.add_control(
-            Box::from(system_fix_selector), 
+            Box::from(system_selector),
             ControlProperties { position : IVec2::zero(), pivot : Pivot::Center, binding : Binding::Center }
         );
+    }
+}
+
+impl ServiceModal for RepairStationModal {
+    fn unfold(&mut self, game : &Game) {
+        self.diagnosed = false;
+        self.unfold_diagnosed = false;
+        self.refresh_diagnosis = false;
+
+        let repair_station = game.city_map.get_service::<RepairStation>(self.selected_service);
+        let price_level = game.city_map.get_price_level();
+
+        let diagnose_cost = UIText::new(self.font.clone(), format!("DIAGNOSE FOR {}$", repair_station.get_diagnosis_cost(price_level)));
+        self.modal.clear_controls();
+        self.modal.add_control(Box::from(diagnose_cost), ControlProperties { position : IVec2::zero(), pivot : Pivot::Center, binding : Binding::Center });
+
+        self.build_diagnosis(&game.ride.car, 0);
+        self.repair_percent = 100.0;
 
         self.modal.start_anim_unfold(1000.0);
     }
-    
+
     fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<ServiceModalEvent> {
         if self.diagnosed && self.unfold_diagnosed && self.modal.anim_state == ModalAnim::Void {
             self.unfold_diagnosed = false;
             self.diagnosed_modal.start_anim_unfold(1000.0);
+            self.repair_modal.start_anim_unfold(1000.0);
         }
 
+        if self.refresh_diagnosis {
+            self.refresh_diagnosis = false;
+            let selected_item = self.get_system_selector().get_selected_item();
+            self.build_diagnosis(&game.ride.car, selected_item);
+        }
+
+        let repair_station = game.city_map.get_service::<RepairStation>(self.selected_service);
+        let price_level = game.city_map.get_price_level();
+        // Modal is updated before it is unfolded for the first time, when there is no diagnosis yet.
+        let (selected, max_repair) = if self.diagnosed {
+            let selected = self.get_system_selector().select_current();
+            (selected, repair_station.get_max_repair(selected, &game.player, &game.ride.car, price_level).0)
+        } else { (CarSystem::Wheels, 100.0) };
+        // Repair might have left less damage or money.
+        self.repair_percent = Math::min(self.repair_percent, max_repair);
+
         for (event, event_type) in input {
             match (event, event_type) {
-                (InputEvent::UIUp, EventType::Pressed) => { 
-                    if self.diagnosed {
-                        unsafe {
-                            let ui_select = &mut *(self.diagnosed_modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<CarSystem>);
-                            ui_select.select_next_in_direction(&IVec2::new(0, 1));
-                        }
-                    }
+                (InputEvent::UIUp, EventType::Pressed) if self.diagnosed => {
+                    self.get_system_selector().select_next_in_direction(&IVec2::new(0, 1));
+                    let selected = self.get_system_selector().select_current();
+                    self.repair_percent = repair_station.get_max_repair(selected, &game.player, &game.ride.car, price_level).0;
                 }
-                (InputEvent::UIDown, EventType::Pressed) => { 
-                    if self.diagnosed {
-                        unsafe {
-                            let ui_select = &mut *(self.diagnosed_modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<CarSystem>);
-                            ui_select.select_next_in_direction(&IVec2::new(0, -1));
-                        }
-                    }
+                (InputEvent::UIDown, EventType::Pressed) if self.diagnosed => {
+                    self.get_system_selector().select_next_in_direction(&IVec2::new(0, -1));
+                    let selected = self.get_system_selector().select_current();
+                    self.repair_percent = repair_station.get_max_repair(selected, &game.player, &game.ride.car, price_level).0;
+                }
+                (InputEvent::UILeft, EventType::Pressed) if self.diagnosed => {
+                    self.repair_percent = Math::max(self.repair_percent - REPAIR_STEP, 0.0);
                 }
-                (InputEvent::UISelect, EventType::Pressed) => { 
+                (InputEvent::UIRight, EventType::Pressed) if self.diagnosed => {
+                    self.repair_percent = Math::min(self.repair_percent + REPAIR_STEP, max_repair);
+                }
+                (InputEvent::UISelect, EventType::Pressed) => {
                     if !self.diagnosed {
+                        // Diagnosis is paid before the results are shown.
+                        if game.player.money < repair_station.get_diagnosis_cost(price_level) { continue; }
+
                         self.modal.start_anim_fold(1000.0);
                         self.diagnosed = true;
                         self.unfold_diagnosed = true;
-                        // TODO : pay for diagnosis.
-                    } else {
-                        let selected = unsafe {
-                            let ui_select = &mut *(self.diagnosed_modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<CarSystem>);
-                            ui_select.select_current()
-                        };
-
-                        let system_name = match selected {
-                            CarSystem::Wheels => { "WHEELS" }
-                            CarSystem::Transmission => { "TRANSMISSION" }
-                            CarSystem::Chase => { "CHASE" }
-                            CarSystem::Engine => { "ENGINE" }
-                            CarSystem::Brake => { "BRAKE" }
-                            CarSystem::Starter => { "STARTER" }
-                        };
-    
-                        println!("fixed {}", system_name);
-
+                        return vec![ServiceModalEvent::UIEvent(UIEvent::ServiceAction(self.selected_service, ServiceAction::DiagnoseCar))];
+                    } else if self.repair_percent > 0.0 {
+                        let selected = self.get_system_selector().select_current();
+                        self.refresh_diagnosis = true;
                         return vec![
                             ServiceModalEvent::UIEvent(
-                                // TODO : determine percent?
-                                UIEvent::ServiceAction(self.selected_service, ServiceAction::FixCarSystem(selected, Percent(100.0)))
+                                UIEvent::ServiceAction(self.selected_service, ServiceAction::FixCarSystem(selected, Percent(self.repair_percent)))
                             )
-                        ]; 
+                        ];
                     }
                 }
-                (InputEvent::UIBack, EventType::Pressed) => { 
-                    if self.diagnosed { &mut self.diagnosed_modal } else { &mut self.modal }.start_anim_fold(1000.0);
-                    
+                (InputEvent::UIBack, EventType::Pressed) => {
+                    if self.diagnosed {
+                        self.diagnosed_modal.start_anim_fold(1000.0);
+                        self.repair_modal.start_anim_fold(1000.0);
+                    } else {
+                        self.modal.start_anim_fold(1000.0);
+                    }
+
                     return vec![ServiceModalEvent::Close];
                 }
                 _ => { }
             }
         }
-        
+
+        if self.diagnosed {
+            let repair_str = if self.repair_percent > 0.0 {
+                format!("FIX {}% FOR {:.2}$", self.repair_percent, repair_station.get_repair_cost(selected, Percent(self.repair_percent), price_level))
+            } else if game.ride.car.get_condition(selected).0 < 100.0 {
+                String::from("NOT ENOUGH MONEY")
+            } else {
+                String::from("NOTHING TO FIX")
+            };
+            self.repair_modal.clear_controls();
+            let repair_text = UIText::new(self.font.clone(), repair_str);
+            self.repair_modal.add_control(Box::from(repair_text), ControlProperties { position : IVec2::zero(), pivot : Pivot::Center, binding : Binding::Center });
+        }
+
         self.modal.update(delta_time);
         self.diagnosed_modal.update(delta_time);
+        self.repair_modal.update(delta_time);
 
         Vec::new()
     }
@@ -154,8 +189,9 @@ impl ServiceModal for RepairStationModal {
 
     fn is_busy(&self) -> bool { self.modal.anim_state != ModalAnim::Void }
 
-    fn draw(&self, buffer : &mut RgbImage) { 
-        self.modal.draw(buffer); 
+    fn draw(&self, buffer : &mut RgbImage) {
+        self.modal.draw(buffer);
         self.diagnosed_modal.draw(buffer);
+        self.repair_modal.draw(buffer);
     }
-}
\ No newline at end of file
+}
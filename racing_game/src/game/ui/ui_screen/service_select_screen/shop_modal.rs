@@ -13,7 +13,9 @@ pub struct ShopModal {
     selected_service : ServiceId,
     font : Rc<Font>,
     resolution : IVec2,
-    pub modal : ModalPage
+    pub modal : ModalPage,
+    // Prices shown in the list are outdated after a purchase.
+    refresh_products : bool
 }
 
 #[derive(Clone)]
@@ -21,29 +23,29 @@ struct ProductSelect(usize);
 
 impl ShopModal {
     pub fn new(resolution : &IVec2, font : Rc<Font>) -> ShopModal {
-        let modal = ModalPage::new(IVec2::new(100, 100), IVec2::new(200, 100), Some(Rgb([150, 150, 150])));
+        let modal = ModalPage::new(IVec2::new(160, 90), IVec2::new(320, 110), Some(Rgb([150, 150, 150])));
         ShopModal { 
             buy_gas_amount : 0,
             selected_service : ServiceId(0),
             font,
             resolution : *resolution,
-            modal
+            modal,
+            refresh_products : false
         }
     }
-}
 
-impl ServiceModal for ShopModal {  
-    fn unfold(&mut self, game : &Game) { 
+    fn get_product_selector(&mut self) -> &mut UISelector<ProductSelect> {
+        unsafe { &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<ProductSelect>) }
+    }
+
+    fn build_products(&mut self, game : &Game, selected_item : usize) {
         let shop = game.city_map.get_service::<Shop>(self.selected_service);
         let mut products = Vec::new();
 
         for i in 0..shop.assortment.len() {
-            let product = &shop.assortment[i];
-            let product_name = match product.product_type {
-                ProductType::Water(size) => { format!("WATER {}L. FOR {}$", size, product.cost) }
-                ProductType::Soda(size) => { format!("SODA {}L. FOR {}$", size, product.cost) }
-                ProductType::Food(size) => { format!("FOOD {}KG. FOR {}$", size, product.cost) }
-            };
+            let cost = shop.get_product_cost(i, game.city_map.get_price_level());
+            let product = &shop.assortment[i].product_type;
+            let product_name = format!("{} {}{} FOR {}$", product.get_name(), product.get_size(), product.get_unit(), cost);
 
             products.push(UISelectorItem::new(
                 Box::from(UIText::new(self.font.clone(), product_name)),
@@ -54,35 +56,40 @@ impl ServiceModal for ShopModal {
 
         let pointer_image = Game::load_image_rgba("ui/pointer.png");
         let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
-        let option_selector = UISelector::<ProductSelect>::new(products, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution.clone(), None);
+        let mut option_selector = UISelector::<ProductSelect>::new(products, SelectionType::Vertical, pointer_image, pointer_offset, self.resolution.clone(), None);
+        option_selector.set_selected_item(selected_item);
         self.modal.clear_controls();
         self.modal.add_control(Box::from(option_selector), ControlProperties { position : IVec2::zero(), pivot : Pivot::LeftBottom, binding : Binding::LeftBottom });
+    }
+}
 
+impl ServiceModal for ShopModal {  
+    fn unfold(&mut self, game : &Game) { 
+        self.refresh_products = false;
+        self.build_products(game, 0);
         self.modal.start_anim_unfold(1000.0); 
     }
 
     fn update(&mut self, game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<ServiceModalEvent> {
+        if self.refresh_products {
+            self.refresh_products = false;
+            let selected_item = self.get_product_selector().get_selected_item();
+            self.build_products(game, selected_item);
+        }
+
         self.modal.update(delta_time);
 
         for (event, event_type) in input {
             match (event, event_type) {
                 (InputEvent::UIDown, EventType::Pressed) => { 
-                    unsafe {
-                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<ProductSelect>);
-                        ui_select.select_next_in_direction(&IVec2::new(0, -1));
-                    }
+                    self.get_product_selector().select_next_in_direction(&IVec2::new(0, -1));
                 }
                 (InputEvent::UIUp, EventType::Pressed) => { 
-                    unsafe {
-                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<ProductSelect>);
-                        ui_select.select_next_in_direction(&IVec2::new(0, 1));
-                    }
+                    self.get_product_selector().select_next_in_direction(&IVec2::new(0, 1));
                 }
                 (InputEvent::UISelect, EventType::Pressed) => { 
-                    let selected = unsafe {
-                        let ui_select = &mut *(self.modal.get_control_mut(0) as *mut dyn UIControl as *mut UISelector<ProductSelect>);
-                        ui_select.select_current()
-                    };
+                    let selected = self.get_product_selector().select_current();
+                    self.refresh_products = true;
 
                     return vec![
                         ServiceModalEvent::UIEvent(
@@ -16,12 +16,20 @@ enum MenuEvents {
     Hostels,
     RepairStations,
     Shops,
+    JobBoards,
+    Garage,
+    Inventory,
+    Save,
+    Load,
 
     Next
 }
 
 pub struct ServicesScreen{
-    menu : UISelector<MenuEvents>
+    menu : UISelector<MenuEvents>,
+    message_page : UIPage,
+    font : Rc<Font>,
+    resolution : IVec2
 }
 
 impl ServicesScreen {
@@ -70,6 +78,56 @@ impl ServicesScreen {
             }, 
             MenuEvents::Shops)
         );
+        // Job boards.
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(font.clone(), String::from("JOB BOARD"))), 
+            ControlProperties { 
+                pivot : Pivot::LeftTop, 
+                position : IVec2::new(20, -100), 
+                binding : Binding::LeftTop 
+            }, 
+            MenuEvents::JobBoards)
+        );
+        // Garage.
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(font.clone(), String::from("GARAGE"))), 
+            ControlProperties { 
+                pivot : Pivot::LeftTop, 
+                position : IVec2::new(20, -120), 
+                binding : Binding::LeftTop 
+            }, 
+            MenuEvents::Garage)
+        );
+        // Inventory.
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(font.clone(), String::from("INVENTORY"))), 
+            ControlProperties { 
+                pivot : Pivot::LeftTop, 
+                position : IVec2::new(20, -140), 
+                binding : Binding::LeftTop 
+            }, 
+            MenuEvents::Inventory)
+        );
+        // Save.
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(font.clone(), String::from("SAVE GAME"))), 
+            ControlProperties { 
+                pivot : Pivot::LeftTop, 
+                position : IVec2::new(20, -180), 
+                binding : Binding::LeftTop 
+            }, 
+            MenuEvents::Save)
+        );
+        // Load.
+        menu_items.push(UISelectorItem::new(
+            Box::from(UIText::new(font.clone(), String::from("LOAD GAME"))), 
+            ControlProperties { 
+                pivot : Pivot::LeftTop, 
+                position : IVec2::new(20, -200), 
+                binding : Binding::LeftTop 
+            }, 
+            MenuEvents::Load)
+        );
         // Next.
         menu_items.push(UISelectorItem::new(
             Box::from(UIText::new(font.clone(), String::from("NEXT"))), 
@@ -84,16 +142,16 @@ impl ServicesScreen {
         let pointer_offset = IVec2::new(-(pointer_image.width() as isize), 0);
         let menu = UISelector::new(menu_items, SelectionType::Vertical, pointer_image, pointer_offset, resolution.clone(), None);
 
-        ServicesScreen { menu }
+        ServicesScreen { menu, message_page : UIPage::new(*resolution, None), font, resolution : *resolution }
     }
 }
 
 impl UIScreen for ServicesScreen {
     fn init(&mut self, game : &Game) {
-
+        self.message_page.clear_controls();
     }   
 
-    fn update(&mut self, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
+    fn update(&mut self, _game : &Game, input : &Vec<(InputEvent, EventType)>, delta_time : f32) -> Vec<UIEvent> {
         for (event, event_type) in input {
             match (event, event_type) {
                 (InputEvent::UIDown, EventType::Pressed) => { self.menu.select_next_in_direction(&IVec2::new(0, -1)); }
@@ -105,6 +163,11 @@ impl UIScreen for ServicesScreen {
                         MenuEvents::Hostels => { return vec![UIEvent::ChangeScreen(Screen::Hostels)]; },
                         MenuEvents::RepairStations => { return vec![UIEvent::ChangeScreen(Screen::RepairStations)]; },
                         MenuEvents::Shops => { return vec![UIEvent::ChangeScreen(Screen::Shops)]; },
+                        MenuEvents::JobBoards => { return vec![UIEvent::ChangeScreen(Screen::JobBoards)]; },
+                        MenuEvents::Garage => { return vec![UIEvent::ChangeScreen(Screen::Garage)]; },
+                        MenuEvents::Inventory => { return vec![UIEvent::ChangeScreen(Screen::Inventory)]; },
+                        MenuEvents::Save => { return vec![UIEvent::SaveGame]; },
+                        MenuEvents::Load => { return vec![UIEvent::LoadGame]; },
 
                         MenuEvents::Next => { return vec![UIEvent::ChangeScreen(Screen::Map)]; } 
                     }
@@ -118,5 +181,18 @@ impl UIScreen for ServicesScreen {
 
     fn render(&self, buffer : &mut RgbImage) {
         self.menu.draw(buffer);
+        self.message_page.draw(buffer);
+    }
+
+    // Save and load results are shown above the next button.
+    fn show_message(&mut self, message : &str) {
+        self.message_page.clear_controls();
+        let lines = self.font.wrap(&self.font.to_printable(message), self.resolution.x as u32 - 40);
+        for (i, line) in lines.into_iter().enumerate() {
+            self.message_page.add_control(
+                Box::from(UIText::new(self.font.clone(), line)),
+                &ControlProperties { pivot : Pivot::LeftBottom, binding : Binding::LeftBottom, position : IVec2::new(20, 80 - 20 * i as isize) }
+            );
+        }
     }
 }
\ No newline at end of file
@@ -1,11 +1,111 @@
 extern crate image;
 
+use std::path::PathBuf;
+
+use engine::common::IVec2;
+
 mod engine;
 mod game;
 
+use engine::backend::*;
+use engine::replay::*;
+use engine::window::WindowParameters;
 use game::*;
+use game::new_game::*;
+
+struct LaunchOptions {
+    headless : bool,
+    frame_count : Option<u32>,
+    output_dir : Option<PathBuf>,
+    record : Option<PathBuf>,
+    replay : Option<PathBuf>,
+    seed : Option<String>,
+    config : Option<PathBuf>,
+    city_count : Option<u32>,
+    map_size : Option<isize>
+}
+
+impl LaunchOptions {
+    // racing_game [--headless] [--frames <count>] [--output <dir>] [--record <file>] [--replay <file>]
+    //             [--seed <code>] [--config <file>] [--cities <count>] [--map-size <size>]
+    fn parse(mut args : impl Iterator<Item = String>) -> LaunchOptions {
+        let mut options = LaunchOptions { 
+            headless : false, frame_count : None, output_dir : None, record : None, replay : None, 
+            seed : None, config : None, city_count : None, map_size : None 
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "--headless" => { options.headless = true; }
+                "--frames" => { options.frame_count = Some(args.next().and_then(|count| count.parse().ok()).expect("incorrect frame count!")); }
+                "--output" => { options.output_dir = Some(PathBuf::from(args.next().expect("output dir is missing!"))); }
+                "--record" => { options.record = Some(PathBuf::from(args.next().expect("record file is missing!"))); }
+                "--replay" => { options.replay = Some(PathBuf::from(args.next().expect("replay file is missing!"))); }
+                "--seed" => { options.seed = Some(args.next().expect("seed is missing!")); }
+                "--config" => { options.config = Some(PathBuf::from(args.next().expect("config file is missing!"))); }
+                "--cities" => { options.city_count = Some(args.next().and_then(|count| count.parse().ok()).expect("incorrect city count!")); }
+                "--map-size" => { options.map_size = Some(args.next().and_then(|size| size.parse().ok()).expect("incorrect map size!")); }
+                _ => { panic!("unknown argument {}", arg); }
+            }
+        }
+
+        options
+    }
+}
 
 fn main() {
-    let mut game = Game::new();
-    game.enter_gameloop();
+    // Skip first because it is executable path.
+    let options = LaunchOptions::parse(std::env::args().skip(1));
+
+    let record = options.replay.as_ref().map(|path| InputRecord::load(path).unwrap_or_else(|e| panic!("{}", e)));
+    let parameters = new_game_parameters(&options, record.as_ref());
+
+    let backend = create_backend(&options, record, &parameters).unwrap_or_else(|e| panic!("{}", e));
+    Game::new(backend, parameters).enter_gameloop().unwrap_or_else(|e| panic!("{}", e));
+}
+
+// Window or headless backend at the bottom, replay and recording are wrapped around it.
+fn create_backend(options : &LaunchOptions, record : Option<InputRecord>, parameters : &NewGameParameters) -> Result<Box<dyn Backend>, String> {
+    let mut backend : Box<dyn Backend> = if options.headless {
+        // When replaying, the record decides when to stop.
+        let default_frame_count = if record.is_some() { u32::MAX } else { 1 };
+        let frame_count = options.frame_count.unwrap_or(default_frame_count);
+        Box::from(HeadlessBackend::new(frame_count, 1.0 / 60.0, options.output_dir.clone())?)
+    } else {
+        Box::from(WindowBackend::open(WindowParameters { width : SCREEN_RESOLUTION.x as u32, height : SCREEN_RESOLUTION.y as u32, title : String::from("title") }))
+    };
+
+    if let Some(record) = record {
+        backend = Box::from(ReplayBackend::new(record, backend));
+    }
+
+    if let Some(path) = &options.record {
+        backend = Box::from(RecordingBackend::new(path, &parameters.to_properties(), backend)?);
+    }
+
+    Ok(backend)
+}
+
+// Command line overrides the config file. Replay uses the parameters it was recorded with unless they are given explicitly.
+fn new_game_parameters(options : &LaunchOptions, record : Option<&InputRecord>) -> NewGameParameters {
+    let mut parameters = NewGameParameters::new(WorldSeed::random());
+
+    if let Some(path) = &options.config {
+        parameters.apply_config(path).unwrap_or_else(|e| panic!("{}", e));
+    }
+    for (key, value) in record.map(|record| record.properties.as_slice()).unwrap_or(&[]) {
+        parameters.apply_property(key, value).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(seed) = &options.seed {
+        parameters.seed = WorldSeed::from_code(seed).unwrap_or_else(|e| panic!("{}", e));
+    }
+    if let Some(city_count) = options.city_count {
+        parameters.generation.city_count = city_count;
+    }
+    if let Some(map_size) = options.map_size {
+        parameters.generation.size = IVec2::new(map_size, map_size);
+    }
+
+    parameters.validate().unwrap_or_else(|e| panic!("{}", e));
+    parameters
 }